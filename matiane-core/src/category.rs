@@ -0,0 +1,385 @@
+use crate::events::{Event, Focused, TimedEvent};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+use thiserror::Error;
+
+pub const FALLBACK_CATEGORY: &str = "Uncategorized";
+
+fn default_fallback() -> String {
+    FALLBACK_CATEGORY.into()
+}
+
+#[derive(Debug, Error)]
+pub enum CategoryError {
+    #[error("Rule for \"{category}\" has an invalid regex: {source}")]
+    InvalidRegex {
+        category: String,
+        #[source]
+        source: regex::Error,
+    },
+
+    #[error("Rule for \"{0}\" does not match on anything.")]
+    EmptyRule(String),
+}
+
+/// Single rule from the config, every condition that is set must match.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RuleConfig {
+    pub category: String,
+    /// Exact application id.
+    pub app_id: Option<String>,
+    /// Regex searched in the window title.
+    pub title: Option<String>,
+    /// Regex searched in the workspace name.
+    pub workspace: Option<String>,
+    /// Executable recorded with the focus, a file name or a full path.
+    pub executable: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct CategoriesConfig {
+    #[serde(default = "default_fallback")]
+    pub fallback: String,
+
+    /// Checked in order, the first match wins.
+    #[serde(default)]
+    pub rules: Vec<RuleConfig>,
+}
+
+impl Default for CategoriesConfig {
+    fn default() -> Self {
+        CategoriesConfig {
+            fallback: default_fallback(),
+            rules: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    category: String,
+    app_id: Option<String>,
+    title: Option<Regex>,
+    workspace: Option<Regex>,
+    executable: Option<String>,
+}
+
+impl Rule {
+    fn new(config: &RuleConfig) -> Result<Self, CategoryError> {
+        if config.app_id.is_none()
+            && config.title.is_none()
+            && config.workspace.is_none()
+            && config.executable.is_none()
+        {
+            return Err(CategoryError::EmptyRule(config.category.clone()));
+        }
+
+        let regex =
+            |pattern: &Option<String>| {
+                pattern.as_deref().map(Regex::new).transpose().map_err(
+                    |source| CategoryError::InvalidRegex {
+                        category: config.category.clone(),
+                        source,
+                    },
+                )
+            };
+
+        Ok(Rule {
+            category: config.category.clone(),
+            app_id: config.app_id.clone(),
+            title: regex(&config.title)?,
+            workspace: regex(&config.workspace)?,
+            executable: config.executable.clone(),
+        })
+    }
+
+    fn matches(&self, focused: &Focused) -> bool {
+        if let Some(app_id) = &self.app_id
+            && *app_id != focused.id
+        {
+            return false;
+        }
+
+        if let Some(title) = &self.title
+            && !title.is_match(&focused.title)
+        {
+            return false;
+        }
+
+        if let Some(workspace) = &self.workspace
+            && !focused
+                .workspace
+                .as_deref()
+                .is_some_and(|name| workspace.is_match(name))
+        {
+            return false;
+        }
+
+        // Older logs have no executable, nothing matches it there.
+        if let Some(executable) = &self.executable
+            && !focused
+                .executable
+                .as_deref()
+                .is_some_and(|path| executable_matches(executable, path))
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+fn executable_matches(expected: &str, path: &str) -> bool {
+    if expected.contains('/') {
+        return path == expected;
+    }
+
+    Path::new(path)
+        .file_name()
+        .is_some_and(|name| name == expected)
+}
+
+/// Compiled categorization rules.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    rules: Vec<Rule>,
+    fallback: String,
+}
+
+impl Rules {
+    pub fn new(config: &CategoriesConfig) -> Result<Self, CategoryError> {
+        let rules = config
+            .rules
+            .iter()
+            .map(Rule::new)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Rules {
+            rules,
+            fallback: config.fallback.clone(),
+        })
+    }
+
+    pub fn fallback(&self) -> &str {
+        &self.fallback
+    }
+
+    pub fn category(&self, focused: &Focused) -> &str {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(focused))
+            .map(|rule| rule.category.as_str())
+            .unwrap_or(&self.fallback)
+    }
+
+    /// Category of the focus event, other events have none.
+    pub fn event_category(&self, event: &TimedEvent) -> Option<&str> {
+        match &event.event {
+            Event::Focused(focused) => Some(self.category(focused)),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            rules: vec![],
+            fallback: default_fallback(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use chrono::{TimeZone, Utc};
+
+    const CONFIG: &str = r#"
+        fallback = "Other"
+
+        [[rules]]
+        category = "Billing"
+        app-id = "firefox"
+        title = "(?i)invoice"
+
+        [[rules]]
+        category = "Browsing"
+        app-id = "firefox"
+
+        [[rules]]
+        category = "Client X"
+        workspace = "client-x$"
+
+        [[rules]]
+        category = "Terminal"
+        executable = "alacritty"
+
+        [[rules]]
+        category = "Editor"
+        executable = "/usr/bin/emacs"
+    "#;
+
+    const ALACRITTY: Option<&str> = Some("/usr/local/bin/alacritty");
+
+    fn rules() -> Result<Rules> {
+        let config: CategoriesConfig = toml::from_str(CONFIG)?;
+        Ok(Rules::new(&config)?)
+    }
+
+    fn focused(id: &str, title: &str, executable: Option<&str>) -> Focused {
+        Focused {
+            title: title.into(),
+            id: id.into(),
+            pid: 1,
+            executable: executable.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    fn timed(event: Event) -> TimedEvent {
+        TimedEvent {
+            timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap(),
+            event,
+        }
+    }
+
+    #[test]
+    fn categories_config_default() -> Result<()> {
+        let config: CategoriesConfig = toml::from_str("")?;
+
+        assert_eq!(config, CategoriesConfig::default());
+        assert_eq!(config.fallback, FALLBACK_CATEGORY);
+
+        let rules = Rules::new(&config)?;
+        assert_eq!(rules.category(&focused("a", "b", None)), FALLBACK_CATEGORY);
+
+        Ok(())
+    }
+
+    #[test]
+    fn categories_first_match_wins() -> Result<()> {
+        let rules = rules()?;
+
+        let invoice = focused("firefox", "Invoice #12 - Mozilla Firefox", None);
+        assert_eq!(rules.category(&invoice), "Billing");
+
+        let news = focused("firefox", "News - Mozilla Firefox", None);
+        assert_eq!(rules.category(&news), "Browsing");
+
+        Ok(())
+    }
+
+    #[test]
+    fn categories_workspace() -> Result<()> {
+        let rules = rules()?;
+
+        let mut client = focused("Alacritty", "zsh", ALACRITTY);
+        client.workspace = Some("5: client-x".into());
+        assert_eq!(rules.category(&client), "Client X");
+
+        // Recorded before the workspaces were logged.
+        client.workspace = None;
+        assert_eq!(rules.category(&client), "Terminal");
+
+        Ok(())
+    }
+
+    #[test]
+    fn categories_executable() -> Result<()> {
+        let rules = rules()?;
+
+        assert_eq!(
+            rules.category(&focused("Alacritty", "zsh", ALACRITTY)),
+            "Terminal"
+        );
+        assert_eq!(
+            rules.category(&focused(
+                "emacs",
+                "init.el",
+                Some("/usr/bin/emacs")
+            )),
+            "Editor"
+        );
+        // Full paths have to match exactly.
+        assert_eq!(
+            rules.category(&focused(
+                "emacs",
+                "init.el",
+                Some("/opt/emacs/bin/emacs")
+            )),
+            "Other"
+        );
+        // Recorded before the executables were logged.
+        assert_eq!(rules.category(&focused("emacs", "init.el", None)), "Other");
+
+        Ok(())
+    }
+
+    #[test]
+    fn categories_recorded_events() -> Result<()> {
+        let rules = rules()?;
+        let events = [
+            timed(Event::Focused(Box::new(focused(
+                "firefox", "Invoice", None,
+            )))),
+            timed(Event::Alive),
+            timed(Event::Focused(Box::new(focused("zsh", "vim", ALACRITTY)))),
+            timed(Event::Idle),
+            timed(Event::Focused(Box::new(focused("mpv", "film", None)))),
+        ];
+
+        let categories: Vec<_> =
+            events.iter().map(|e| rules.event_category(e)).collect();
+
+        assert_eq!(
+            categories,
+            vec![Some("Billing"), None, Some("Terminal"), None, Some("Other")]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn categories_invalid_rules() -> Result<()> {
+        let config: CategoriesConfig = toml::from_str(
+            r#"
+            [[rules]]
+            category = "Broken"
+            title = "(unclosed"
+            "#,
+        )?;
+
+        assert!(matches!(
+            Rules::new(&config),
+            Err(CategoryError::InvalidRegex { category, .. }) if category == "Broken"
+        ));
+
+        let config: CategoriesConfig = toml::from_str(
+            r#"
+            [[rules]]
+            category = "Everything"
+            "#,
+        )?;
+
+        assert!(matches!(
+            Rules::new(&config),
+            Err(CategoryError::EmptyRule(category)) if category == "Everything"
+        ));
+
+        let unknown = toml::from_str::<CategoriesConfig>(
+            r#"
+            [[rules]]
+            category = "Typo"
+            app_id = "firefox"
+            "#,
+        );
+        assert!(unknown.is_err());
+
+        Ok(())
+    }
+}
@@ -1,22 +1,132 @@
 use crate::xdg;
-use serde::Deserialize;
+use log::warn;
+use serde::{Deserialize, Deserializer};
 use std::path::PathBuf;
+use std::time::Duration;
+
+pub const LIVE_INTERVAL: Duration = Duration::from_secs(60);
 
 fn default_state_dir() -> PathBuf {
     xdg::data_dir(Some(crate::NAME))
 }
 
+pub fn deserialize_interval<'de, D>(
+    deserializer: D,
+) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let secs = u64::deserialize(deserializer)?;
+    Ok(Duration::from_secs(secs))
+}
+
+fn deserialize_optional_interval<'de, D>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_interval(deserializer).map(Some)
+}
+
 #[derive(Debug, PartialEq, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct GeneralConfig {
     #[serde(default = "default_state_dir")]
     pub state_dir: PathBuf,
+
+    /// How often the daemon writes the liveness event, readers use it to
+    /// find where the logging stopped. See `live_interval()`.
+    #[serde(default, deserialize_with = "deserialize_optional_interval")]
+    pub live_interval: Option<Duration>,
+
+    /// Counts the idle time under an idle inhibitor or a fullscreen window
+    /// as active time, instead of reporting it apart.
+    #[serde(default)]
+    pub passive_as_active: bool,
 }
 
 impl Default for GeneralConfig {
     fn default() -> Self {
         GeneralConfig {
             state_dir: default_state_dir(),
+            live_interval: None,
+            passive_as_active: false,
         }
     }
 }
+
+impl GeneralConfig {
+    /// Liveness interval, the default one unless it is set.
+    pub fn live_interval(&self) -> Duration {
+        self.live_interval.unwrap_or(LIVE_INTERVAL)
+    }
+
+    /// Takes over the deprecated options of the other sections from the raw
+    /// config. The ones set in `[general]` win.
+    pub fn apply_deprecated(
+        &mut self,
+        raw: &str,
+    ) -> Result<(), toml::de::Error> {
+        let deprecated: Deprecated = toml::from_str(raw)?;
+
+        if let Some(live_interval) = deprecated.sway.live_interval {
+            warn!("[sway] live-interval is deprecated, move it to [general].");
+            self.live_interval.get_or_insert(live_interval);
+        }
+
+        Ok(())
+    }
+}
+
+/// Sections of the config that are read for their deprecated options only.
+#[derive(Debug, Default, Deserialize)]
+struct Deprecated {
+    #[serde(default)]
+    sway: DeprecatedSway,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct DeprecatedSway {
+    /// Moved to `[general]`.
+    #[serde(default, deserialize_with = "deserialize_optional_interval")]
+    live_interval: Option<Duration>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[derive(Debug, Deserialize)]
+    struct Config {
+        #[serde(default)]
+        general: GeneralConfig,
+    }
+
+    fn live_interval(raw: &str) -> Result<Duration> {
+        let mut config: Config = toml::from_str(raw)?;
+        config.general.apply_deprecated(raw)?;
+
+        Ok(config.general.live_interval())
+    }
+
+    #[test]
+    fn deprecated_live_interval() -> Result<()> {
+        assert_eq!(live_interval("")?, LIVE_INTERVAL);
+        assert_eq!(
+            live_interval("[sway]\nlive-interval = 120")?,
+            Duration::from_secs(120)
+        );
+        // Set to the default explicitly, still wins.
+        assert_eq!(
+            live_interval(
+                "[general]\nlive-interval = 60\n[sway]\nlive-interval = 120"
+            )?,
+            LIVE_INTERVAL
+        );
+
+        Ok(())
+    }
+}
@@ -1,14 +1,26 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Focused {
     pub title: String,
     pub id: String,
     pub pid: i32,
+    /// Name of the workspace the window is on, missing in older logs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    /// Workspace number, when the name starts with one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_num: Option<i32>,
+    /// Name of the output the workspace is on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Path of the process executable, read when the window got the focus.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executable: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
 pub enum Event {
     Focused(Box<Focused>),
@@ -22,6 +34,22 @@ pub enum Event {
     Idle,
     /// swayidle: Back to active state
     Active,
+    /// Went idle while an idle inhibitor or a fullscreen window kept the user
+    /// present, e.g. watching a video. Ends with `Active`.
+    Passive,
+    /// Tracking was paused by the user, nothing but the liveness is recorded
+    /// until `Resumed` or the daemon stops.
+    Paused,
+    /// Tracking was resumed after `Paused`.
+    Resumed,
+    /// Focused window is excluded by the privacy rules, nothing about it is
+    /// recorded.
+    Private,
+    /// Lost the sway connection, the focus is unknown until the next focus
+    /// event.
+    Disconnected,
+    /// Note left by the user, does not change the state.
+    Annotation(String),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -0,0 +1,361 @@
+pub mod activitywatch;
+pub mod csv;
+pub mod ics;
+pub mod jsonl;
+pub mod org;
+pub mod timewarrior;
+
+use crate::category::Rules;
+use crate::session::{Interval, IntervalState};
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Largest pause between two records that still belong to one work block.
+pub const MERGE_GAP: TimeDelta = TimeDelta::minutes(5);
+
+/// Shortest work block worth exporting.
+pub const MIN_BLOCK: TimeDelta = TimeDelta::minutes(1);
+
+fn default_merge_gap() -> u32 {
+    MERGE_GAP.num_minutes() as u32
+}
+
+fn default_min_block() -> u32 {
+    MIN_BLOCK.num_minutes() as u32
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One row per focus interval.
+    Csv,
+    /// One JSON object per focus interval.
+    Jsonl,
+    /// iCalendar with one event per work block.
+    Ics,
+    /// ActivityWatch window and AFK buckets.
+    ActivityWatch,
+    /// `timew import` JSON with one interval per category block.
+    Timewarrior,
+    /// Org-mode `CLOCK:` entries under a heading per category.
+    Org,
+}
+
+/// `[export]` section of the config, in minutes.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExportConfig {
+    #[serde(default = "default_merge_gap")]
+    pub merge_gap: u32,
+    #[serde(default = "default_min_block")]
+    pub min_block: u32,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        ExportConfig {
+            merge_gap: default_merge_gap(),
+            min_block: default_min_block(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportOptions {
+    /// Host the ActivityWatch buckets belong to.
+    pub hostname: String,
+    /// Largest pause within a block, for the formats made of blocks.
+    pub merge_gap: TimeDelta,
+    /// Blocks with less active time are left out.
+    pub min_block: TimeDelta,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            hostname: activitywatch::hostname(),
+            merge_gap: MERGE_GAP,
+            min_block: MIN_BLOCK,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Failed to encode the export: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Active focus interval with its category.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Record {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// In seconds.
+    #[serde(serialize_with = "serialize_seconds")]
+    pub duration: TimeDelta,
+    pub app: String,
+    pub title: String,
+    pub category: String,
+}
+
+/// Records following each other closely enough, merged into one stretch of
+/// work.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Active time within the block, the gaps are not counted.
+    pub active: TimeDelta,
+    /// Totals per category, the longest first.
+    pub categories: Vec<(String, TimeDelta)>,
+    /// Totals per application, the longest first.
+    pub apps: Vec<(String, TimeDelta)>,
+}
+
+impl Block {
+    /// Category the block was mostly spent on.
+    pub fn category(&self) -> &str {
+        self.categories
+            .first()
+            .map(|(category, _)| category.as_str())
+            .unwrap_or_default()
+    }
+}
+
+/// Active intervals with a focused window, in order.
+pub fn records(intervals: &[Interval], rules: &Rules) -> Vec<Record> {
+    intervals
+        .iter()
+        .filter(|interval| interval.state == IntervalState::Active)
+        .filter_map(|interval| {
+            let focused = interval.focused.as_ref()?;
+
+            Some(Record {
+                start: interval.start,
+                end: interval.end,
+                duration: interval.duration(),
+                app: focused.id.clone(),
+                title: focused.title.clone(),
+                category: rules.category(focused).into(),
+            })
+        })
+        .collect()
+}
+
+/// Merges the records into blocks, a pause longer than `gap` starts a new
+/// one. Blocks with less than `min_block` of active time are left out.
+pub fn blocks(
+    records: &[Record],
+    gap: TimeDelta,
+    min_block: TimeDelta,
+) -> Vec<Block> {
+    runs(records, |last, record| record.start - last.end <= gap)
+        .iter()
+        .map(|run| block(run))
+        .filter(|block| block.active >= min_block)
+        .collect()
+}
+
+/// Like [`blocks`], but every block has a single category. The short runs
+/// are left out first, so a quick look elsewhere does not split a block.
+pub fn category_blocks(
+    records: &[Record],
+    gap: TimeDelta,
+    min_block: TimeDelta,
+) -> Vec<Block> {
+    let joins = |last: &Record, record: &Record| {
+        record.category == last.category && record.start - last.end <= gap
+    };
+
+    let kept: Vec<Record> = runs(records, joins)
+        .into_iter()
+        .filter(|run| active(run) >= min_block)
+        .flatten()
+        .cloned()
+        .collect();
+
+    runs(&kept, joins).iter().map(|run| block(run)).collect()
+}
+
+/// Splits the records where `joins` does not hold for the neighbours.
+fn runs(
+    records: &[Record],
+    joins: impl Fn(&Record, &Record) -> bool,
+) -> Vec<Vec<&Record>> {
+    let mut runs: Vec<Vec<&Record>> = vec![];
+
+    for record in records {
+        match runs.last_mut() {
+            Some(run) if joins(run[run.len() - 1], record) => run.push(record),
+            _ => runs.push(vec![record]),
+        }
+    }
+
+    runs
+}
+
+fn active(records: &[&Record]) -> TimeDelta {
+    records.iter().map(|record| record.duration).sum()
+}
+
+fn block(records: &[&Record]) -> Block {
+    let mut categories: HashMap<&str, TimeDelta> = HashMap::new();
+    let mut apps: HashMap<&str, TimeDelta> = HashMap::new();
+
+    for record in records {
+        *categories.entry(&record.category).or_default() += record.duration;
+        *apps.entry(&record.app).or_default() += record.duration;
+    }
+
+    Block {
+        start: records[0].start,
+        end: records[records.len() - 1].end,
+        active: active(records),
+        categories: sorted(categories),
+        apps: sorted(apps),
+    }
+}
+
+fn sorted(totals: HashMap<&str, TimeDelta>) -> Vec<(String, TimeDelta)> {
+    let mut totals: Vec<_> = totals
+        .into_iter()
+        .map(|(name, duration)| (name.to_owned(), duration))
+        .collect();
+
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
+
+pub fn export(
+    intervals: &[Interval],
+    rules: &Rules,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Result<String, ExportError> {
+    let records = records(intervals, rules);
+
+    let (gap, min_block) = (options.merge_gap, options.min_block);
+
+    match format {
+        ExportFormat::Csv => Ok(csv::render(&records)),
+        ExportFormat::Jsonl => jsonl::render(&records),
+        ExportFormat::Ics => Ok(ics::render(&blocks(&records, gap, min_block))),
+        ExportFormat::ActivityWatch => {
+            activitywatch::render(intervals, &options.hostname)
+        }
+        ExportFormat::Timewarrior => {
+            timewarrior::render(&category_blocks(&records, gap, min_block))
+        }
+        ExportFormat::Org => Ok(org::render(
+            &category_blocks(&records, gap, min_block),
+            &Local,
+        )),
+    }
+}
+
+/// UTC timestamp in the basic ISO 8601 format.
+fn basic_timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn serialize_seconds<S>(duration: &TimeDelta, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    s.serialize_i64(duration.num_seconds())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{at, interval};
+
+    #[test]
+    fn export_blocks() {
+        let intervals = vec![
+            interval(0, 10, IntervalState::Active, Some("firefox")),
+            interval(10, 12, IntervalState::Idle, None),
+            interval(12, 15, IntervalState::Active, Some("zsh")),
+            interval(15, 16, IntervalState::Passive, Some("mpv")),
+            interval(16, 30, IntervalState::Offline, None),
+            interval(30, 40, IntervalState::Active, Some("zsh")),
+        ];
+
+        let records = records(&intervals, &Rules::default());
+        assert_eq!(records.len(), 3);
+
+        let blocks = blocks(&records, MERGE_GAP, TimeDelta::zero());
+        assert_eq!(blocks.len(), 2);
+
+        assert_eq!((blocks[0].start, blocks[0].end), (at(0), at(15)));
+        assert_eq!(blocks[0].active, TimeDelta::minutes(13));
+        assert_eq!(
+            blocks[0].apps,
+            vec![
+                ("firefox".into(), TimeDelta::minutes(10)),
+                ("zsh".into(), TimeDelta::minutes(3)),
+            ]
+        );
+        assert_eq!(blocks[0].category(), "Uncategorized");
+
+        assert_eq!((blocks[1].start, blocks[1].end), (at(30), at(40)));
+    }
+
+    #[test]
+    fn export_category_blocks() {
+        let record = |start, end, category: &str| Record {
+            start: at(start),
+            end: at(end),
+            duration: at(end) - at(start),
+            app: "app".into(),
+            title: "title".into(),
+            category: category.into(),
+        };
+
+        let records = vec![
+            record(0, 10, "Development"),
+            record(10, 11, "Communication"),
+            record(11, 20, "Development"),
+            record(30, 40, "Communication"),
+            record(40, 41, "Development"),
+        ];
+
+        let blocks =
+            category_blocks(&records, MERGE_GAP, TimeDelta::minutes(2));
+        let spans: Vec<_> = blocks
+            .iter()
+            .map(|block| {
+                (block.start, block.end, block.active, block.category())
+            })
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![
+                (at(0), at(20), TimeDelta::minutes(19), "Development"),
+                (at(30), at(40), TimeDelta::minutes(10), "Communication"),
+            ]
+        );
+    }
+
+    #[test]
+    fn deserialize_export_config() -> anyhow::Result<()> {
+        let decoded = toml::from_str::<ExportConfig>("min-block = 10")?;
+
+        assert_eq!(
+            decoded,
+            ExportConfig {
+                merge_gap: 5,
+                min_block: 10,
+            }
+        );
+        assert_eq!(
+            toml::from_str::<ExportConfig>("")?,
+            ExportConfig::default()
+        );
+
+        Ok(())
+    }
+}
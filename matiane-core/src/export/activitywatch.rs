@@ -0,0 +1,159 @@
+// https://docs.activitywatch.net/en/latest/buckets-and-events.html
+
+use super::ExportError;
+use crate::import::activitywatch::{
+    AFK, AFK_BUCKET, Bucket, Event, EventData, Export, NOT_AFK, WINDOW_BUCKET,
+};
+use crate::session::{Interval, IntervalState};
+use chrono::{DateTime, Utc};
+
+/// Watchers the buckets stand in for, their ids start with these.
+const WINDOW_WATCHER: &str = "aw-watcher-window";
+const AFK_WATCHER: &str = "aw-watcher-afk";
+
+/// Host name of the machine, ActivityWatch groups the buckets by it.
+pub fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|hostname| hostname.trim().to_owned())
+        .ok()
+        .filter(|hostname| !hostname.is_empty())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+/// Window and AFK buckets of the intervals, ready for the aw-server import.
+pub fn render(
+    intervals: &[Interval],
+    hostname: &str,
+) -> Result<String, ExportError> {
+    let windows = merge(intervals.iter().filter_map(|interval| {
+        let focused = interval.focused.as_ref()?;
+
+        Some((
+            interval,
+            EventData {
+                app: Some(focused.id.clone()),
+                title: Some(focused.title.clone()),
+                ..Default::default()
+            },
+        ))
+    }));
+
+    let afk = merge(intervals.iter().filter_map(|interval| {
+        let status = match interval.state {
+            IntervalState::Active | IntervalState::Passive => NOT_AFK,
+            IntervalState::Idle | IntervalState::Asleep => AFK,
+            // Nothing was watched.
+            IntervalState::Paused | IntervalState::Offline => return None,
+        };
+
+        Some((
+            interval,
+            EventData {
+                status: Some(status.into()),
+                ..Default::default()
+            },
+        ))
+    }));
+
+    let created = intervals
+        .first()
+        .map(|interval| interval.start)
+        .unwrap_or_default();
+
+    let buckets = [
+        (WINDOW_WATCHER, WINDOW_BUCKET, windows),
+        (AFK_WATCHER, AFK_BUCKET, afk),
+    ]
+    .into_iter()
+    .map(|(watcher, kind, events)| {
+        let id = format!("{}_{}", watcher, hostname);
+        let bucket = Bucket {
+            id: id.clone(),
+            created: Some(created),
+            kind: kind.into(),
+            client: crate::NAME.into(),
+            hostname: hostname.into(),
+            events,
+        };
+
+        (id, bucket)
+    })
+    .collect();
+
+    let mut out = serde_json::to_string_pretty(&Export { buckets })?;
+    out.push('\n');
+
+    Ok(out)
+}
+
+/// Joins the touching intervals with the same data into one event, like
+/// aw-server merges the heartbeats.
+fn merge<'a>(
+    intervals: impl Iterator<Item = (&'a Interval, EventData)>,
+) -> Vec<Event> {
+    let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>, EventData)> = vec![];
+
+    for (interval, data) in intervals {
+        match merged.last_mut() {
+            Some((_, end, last)) if *end == interval.start && *last == data => {
+                *end = interval.end;
+            }
+            _ => merged.push((interval.start, interval.end, data)),
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(start, end, data)| Event {
+            timestamp: start,
+            duration: (end - start).num_milliseconds() as f64 / 1000.0,
+            data,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{at, interval};
+    use anyhow::Result;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn activitywatch_round_trip() -> Result<()> {
+        let intervals = vec![
+            interval(0, 5, IntervalState::Active, Some("zsh")),
+            // Split at midnight or by a refocus, merged back.
+            interval(5, 10, IntervalState::Active, Some("zsh")),
+            interval(10, 12, IntervalState::Idle, None),
+            interval(12, 15, IntervalState::Active, Some("firefox")),
+            interval(15, 20, IntervalState::Offline, None),
+        ];
+
+        let rendered = render(&intervals, "laptop")?;
+        let export = Export::parse(&rendered)?;
+
+        let window = &export.buckets["aw-watcher-window_laptop"];
+        assert_eq!(window.kind, WINDOW_BUCKET);
+        assert_eq!(window.created, Some(at(0)));
+        assert_eq!(window.events.len(), 2);
+        assert_eq!(window.events[0].duration, 600.0);
+
+        let afk = &export.buckets["aw-watcher-afk_laptop"];
+        let statuses: Vec<_> = afk
+            .events
+            .iter()
+            .map(|event| event.data.status.as_deref())
+            .collect();
+        assert_eq!(statuses, [Some(NOT_AFK), Some(AFK), Some(NOT_AFK)]);
+
+        // Reads back the same, but for the offline time.
+        let imported = export.intervals(None)?;
+        assert_eq!(imported.len(), 3);
+        assert_eq!(imported[0].duration(), TimeDelta::minutes(10));
+        assert_eq!(imported[1], interval(10, 12, IntervalState::Idle, None));
+        assert_eq!(imported[2].end, at(15));
+
+        Ok(())
+    }
+}
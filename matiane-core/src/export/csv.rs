@@ -0,0 +1,47 @@
+use super::Record;
+use chrono::SecondsFormat;
+use std::fmt::Write;
+
+pub const HEADER: &str = "start,end,duration,app,title,category";
+
+pub fn render(records: &[Record]) -> String {
+    let mut out = String::from(HEADER);
+    out.push('\n');
+
+    for record in records {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            record.start.to_rfc3339_opts(SecondsFormat::Secs, true),
+            record.end.to_rfc3339_opts(SecondsFormat::Secs, true),
+            record.duration.num_seconds(),
+            field(&record.app),
+            field(&record.title),
+            field(&record.category),
+        );
+    }
+
+    out
+}
+
+/// Quotes the field when it has to be.
+pub fn field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_field_escaping() {
+        assert_eq!(field("plain"), "plain");
+        assert_eq!(field("a,b"), "\"a,b\"");
+        assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(field("two\nlines"), "\"two\nlines\"");
+    }
+}
@@ -0,0 +1,115 @@
+// https://datatracker.ietf.org/doc/html/rfc5545
+
+use super::{Block, basic_timestamp};
+use crate::summary::format_duration;
+
+const PRODID: &str = "-//matiane//matiane//EN";
+
+/// Longest content line in octets, without the line break.
+const LINE_LENGTH: usize = 75;
+
+pub fn render(blocks: &[Block]) -> String {
+    let mut out = String::new();
+
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(&mut out, &format!("PRODID:{}", PRODID));
+    line(&mut out, "CALSCALE:GREGORIAN");
+
+    for block in blocks {
+        event(&mut out, block);
+    }
+
+    line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn event(out: &mut String, block: &Block) {
+    let start = basic_timestamp(block.start);
+    let end = basic_timestamp(block.end);
+
+    let mut description =
+        vec![format!("Active {}", format_duration(block.active))];
+    description.extend(block.apps.iter().map(|(app, duration)| {
+        format!("{} {}", app, format_duration(*duration))
+    }));
+
+    let categories: Vec<String> = block
+        .categories
+        .iter()
+        .map(|(category, _)| text(category))
+        .collect();
+
+    line(out, "BEGIN:VEVENT");
+    line(out, &format!("UID:{}-{}@{}", start, end, crate::NAME));
+    // Blocks are rebuilt from the log, they last changed when they ended.
+    line(out, &format!("DTSTAMP:{}", end));
+    line(out, &format!("DTSTART:{}", start));
+    line(out, &format!("DTEND:{}", end));
+    line(out, &format!("SUMMARY:{}", text(block.category())));
+    line(
+        out,
+        &format!("DESCRIPTION:{}", text(&description.join("\n"))),
+    );
+    line(out, &format!("CATEGORIES:{}", categories.join(",")));
+    line(out, "END:VEVENT");
+}
+
+/// Escapes a TEXT value.
+fn text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Writes a content line, folded on the character boundaries.
+fn line(out: &mut String, content: &str) {
+    let mut length = 0;
+
+    for c in content.chars() {
+        if length + c.len_utf8() > LINE_LENGTH {
+            out.push_str("\r\n ");
+            // The leading space counts towards the folded line.
+            length = 1;
+        }
+
+        out.push(c);
+        length += c.len_utf8();
+    }
+
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ics_text_escaping() {
+        assert_eq!(text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn ics_line_folding() {
+        let mut out = String::new();
+        let content = format!("SUMMARY:{}", "ა".repeat(30));
+        line(&mut out, &content);
+
+        let lines: Vec<&str> = out.split_terminator("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= LINE_LENGTH));
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(lines.concat().replacen(" ", "", 1), content);
+    }
+}
@@ -0,0 +1,12 @@
+use super::{ExportError, Record};
+
+pub fn render(records: &[Record]) -> Result<String, ExportError> {
+    let mut out = String::new();
+
+    for record in records {
+        out.push_str(&serde_json::to_string(record)?);
+        out.push('\n');
+    }
+
+    Ok(out)
+}
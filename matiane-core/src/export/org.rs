@@ -0,0 +1,59 @@
+// https://orgmode.org/manual/Clocking-Work-Time.html
+
+use super::Block;
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use std::fmt::Write;
+
+/// Heading per category with the blocks as clock lines in its logbook. The
+/// category with the most time goes first, the newest clock is on top.
+pub fn render<Tz: TimeZone>(blocks: &[Block], tz: &Tz) -> String {
+    let mut categories: Vec<(&str, TimeDelta, Vec<&Block>)> = vec![];
+
+    for block in blocks {
+        let category = block.category();
+
+        match categories.iter_mut().find(|(name, ..)| *name == category) {
+            Some((_, total, blocks)) => {
+                *total += block.active;
+                blocks.push(block);
+            }
+            None => categories.push((category, block.active, vec![block])),
+        }
+    }
+
+    categories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut out = String::new();
+
+    for (category, _, blocks) in categories {
+        let _ = writeln!(out, "* {}", category);
+        out.push_str(":LOGBOOK:\n");
+
+        for block in blocks.iter().rev() {
+            // Org counts the whole minutes between the timestamps.
+            let minutes =
+                block.end.timestamp() / 60 - block.start.timestamp() / 60;
+
+            let _ = writeln!(
+                out,
+                "CLOCK: [{}]--[{}] => {:2}:{:02}",
+                timestamp(block.start, tz),
+                timestamp(block.end, tz),
+                minutes / 60,
+                minutes % 60,
+            );
+        }
+
+        out.push_str(":END:\n");
+    }
+
+    out
+}
+
+/// Inactive timestamp in the local time, without the brackets.
+fn timestamp<Tz: TimeZone>(at: DateTime<Utc>, tz: &Tz) -> String {
+    at.with_timezone(tz)
+        .naive_local()
+        .format("%Y-%m-%d %a %H:%M")
+        .to_string()
+}
@@ -0,0 +1,52 @@
+// https://timewarrior.net/docs/api/
+
+use super::{Block, ExportError, basic_timestamp};
+use crate::summary::format_duration;
+use serde::Serialize;
+
+/// Interval as `timew export` writes it and `timew import` reads it back.
+#[derive(Serialize)]
+struct Entry<'a> {
+    start: String,
+    end: String,
+    tags: [&'a str; 1],
+    /// Time per application.
+    annotation: String,
+}
+
+impl<'a> From<&'a Block> for Entry<'a> {
+    fn from(block: &'a Block) -> Self {
+        let apps: Vec<String> = block
+            .apps
+            .iter()
+            .map(|(app, duration)| {
+                format!("{} {}", app, format_duration(*duration))
+            })
+            .collect();
+
+        Entry {
+            start: basic_timestamp(block.start),
+            end: basic_timestamp(block.end),
+            tags: [block.category()],
+            annotation: apps.join(", "),
+        }
+    }
+}
+
+/// JSON array with an interval per line, tagged with the category.
+pub fn render(blocks: &[Block]) -> Result<String, ExportError> {
+    let entries = blocks
+        .iter()
+        .map(|block| serde_json::to_string(&Entry::from(block)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut out = String::from("[\n");
+
+    if !entries.is_empty() {
+        out.push_str(&entries.join(",\n"));
+        out.push('\n');
+    }
+
+    out.push_str("]\n");
+    Ok(out)
+}
@@ -0,0 +1,343 @@
+pub mod activitywatch;
+
+use crate::events::{Event, TimedEvent};
+use crate::session::{Interval, IntervalState, LIVE_GRACE, LOOKBACK};
+use crate::store::{
+    EventReader, EventWriter, StoreError, get_filename_by_date, list_dates,
+};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Where the rewritten day logs are put together before they replace the
+/// old ones.
+const STAGING_DIR: &str = ".import";
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Failed to decode the import: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Import has several hosts, pick one of: {}", .0.join(", "))]
+    Hosts(Vec<String>),
+    #[error("Import has no buckets from {0:?}")]
+    UnknownHost(String),
+    #[error("Import failed on the log: {0}")]
+    Store(#[from] StoreError),
+}
+
+/// What the import has added to the log.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportSummary {
+    /// Events written, the existing ones not counted.
+    pub events: usize,
+    /// Day logs that were written.
+    pub dates: Vec<NaiveDate>,
+    /// Imported time left out, because the log already covers it.
+    pub overlap: TimeDelta,
+}
+
+type Range = (DateTime<Utc>, DateTime<Utc>);
+
+/// Writes the intervals into the day logs in `dir`, as the daemon would
+/// have logged them. The time the log already covers is left out and the
+/// existing events are kept.
+///
+/// Every day log it touches is written anew and moved over the old one, the
+/// daemon must not be running meanwhile.
+pub async fn import(
+    dir: &Path,
+    intervals: &[Interval],
+    live_interval: Duration,
+) -> Result<ImportSummary, ImportError> {
+    let mut summary = ImportSummary::default();
+
+    let (Some(from), Some(to)) = (
+        intervals.iter().map(|interval| interval.start).min(),
+        intervals.iter().map(|interval| interval.end).max(),
+    ) else {
+        return Ok(summary);
+    };
+
+    let live = TimeDelta::from_std(live_interval)
+        .unwrap_or(LOOKBACK)
+        .max(TimeDelta::seconds(1));
+
+    let existing = read_events(dir, from - LOOKBACK, to + LOOKBACK).await?;
+    let covered = covered(&existing, live);
+
+    let mut kept = vec![];
+    for interval in intervals {
+        let parts = subtract(interval, &covered);
+        let duration: TimeDelta = parts.iter().map(Interval::duration).sum();
+
+        summary.overlap += interval.duration() - duration;
+        kept.extend(parts);
+    }
+    kept.sort_by_key(|interval| interval.start);
+
+    let seen: Vec<_> = existing.iter().map(|event| event.timestamp).collect();
+    let mut events = to_events(&kept, live, &seen);
+
+    if events.is_empty() {
+        return Ok(summary);
+    }
+
+    let dates: BTreeSet<NaiveDate> = events
+        .iter()
+        .map(|event| event.timestamp.date_naive())
+        .collect();
+
+    summary.events = events.len();
+    summary.dates = dates.iter().copied().collect();
+
+    // On a tie the imported events go first, they end where the log starts.
+    events.extend(
+        existing
+            .into_iter()
+            .filter(|event| dates.contains(&event.timestamp.date_naive())),
+    );
+    events.sort_by_key(|event| event.timestamp);
+
+    write_logs(dir, &events).await?;
+
+    Ok(summary)
+}
+
+/// Every event in `[from, to)`, in order. A malformed line stops the import,
+/// it would be lost when its day log is written anew.
+async fn read_events(
+    dir: &Path,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<TimedEvent>, StoreError> {
+    let mut reader = EventReader::open(dir.to_path_buf(), from, to).await?;
+    let mut events = vec![];
+
+    while let Some(event) = reader.next().await? {
+        events.push(event);
+    }
+
+    events.sort_by_key(|event| event.timestamp);
+    Ok(events)
+}
+
+/// Writes the day logs into the staging directory first, then moves them
+/// over the old ones.
+async fn write_logs(
+    dir: &Path,
+    events: &[TimedEvent],
+) -> Result<(), StoreError> {
+    let staging = dir.join(STAGING_DIR);
+
+    // Left behind by an import that failed halfway.
+    if tokio::fs::try_exists(&staging).await? {
+        tokio::fs::remove_dir_all(&staging).await?;
+    }
+    tokio::fs::create_dir_all(dir).await?;
+
+    let mut writer =
+        EventWriter::open(staging.clone(), events[0].timestamp).await?;
+
+    for event in events {
+        writer.write(event).await?;
+    }
+
+    writer.flush().await?;
+    drop(writer);
+
+    for date in list_dates(&staging).await? {
+        let filename = get_filename_by_date(date);
+        tokio::fs::rename(staging.join(&filename), dir.join(&filename)).await?;
+    }
+
+    tokio::fs::remove_dir(&staging).await?;
+
+    Ok(())
+}
+
+/// Time the daemon was logging: one live interval after every event, joined
+/// where the events are too close for a gap.
+fn covered(events: &[TimedEvent], live: TimeDelta) -> Vec<Range> {
+    let mut ranges: Vec<Range> = vec![];
+
+    for event in events {
+        let at = event.timestamp;
+
+        match ranges.last_mut() {
+            Some((_, end)) if at <= *end + LIVE_GRACE => {
+                *end = (*end).max(at + live);
+            }
+            _ => ranges.push((at, at + live)),
+        }
+    }
+
+    ranges
+}
+
+/// Parts of the interval outside of the sorted ranges.
+fn subtract(interval: &Interval, ranges: &[Range]) -> Vec<Interval> {
+    let mut parts = vec![];
+    let mut start = interval.start;
+
+    for (from, to) in ranges {
+        if *to <= start {
+            continue;
+        }
+
+        if *from >= interval.end {
+            break;
+        }
+
+        parts.extend(interval.clip(start, *from));
+        start = start.max(*to);
+    }
+
+    parts.extend(interval.clip(start, interval.end));
+    parts
+}
+
+/// Events that sessionize back into the sorted intervals, with a liveness
+/// event every live interval. `seen` are the sorted timestamps of the
+/// existing events, whatever state they leave behind is reset.
+fn to_events(
+    intervals: &[Interval],
+    live: TimeDelta,
+    seen: &[DateTime<Utc>],
+) -> Vec<TimedEvent> {
+    let mut events = vec![];
+    let mut push = |timestamp, event| {
+        events.push(TimedEvent { timestamp, event });
+    };
+
+    let mut last: Option<&Interval> = None;
+
+    for interval in intervals {
+        let start = interval.start;
+
+        match last {
+            Some(last) if start - last.end <= LIVE_GRACE => {
+                if last.state == IntervalState::Idle {
+                    push(start, Event::Active);
+                }
+            }
+            _ => {
+                if let Some(last) = last {
+                    end(&mut push, last);
+                }
+
+                if seen.partition_point(|at| *at < start) > 0 {
+                    push(start, Event::Resumed);
+                    push(start, Event::Awake);
+                    push(start, Event::Active);
+                }
+            }
+        }
+
+        match (&interval.state, &interval.focused) {
+            (IntervalState::Idle, _) => push(start, Event::Idle),
+            (_, Some(focused)) => {
+                push(start, Event::Focused(Box::new(focused.clone())))
+            }
+            (_, None) => push(start, Event::Private),
+        }
+
+        let mut at = start + live;
+        while at < interval.end {
+            push(at, Event::Alive);
+            at += live;
+        }
+
+        last = Some(interval);
+    }
+
+    if let Some(last) = last {
+        end(&mut push, last);
+    }
+
+    events
+}
+
+/// Stops the time from going to the interval past its end.
+fn end(push: &mut impl FnMut(DateTime<Utc>, Event), interval: &Interval) {
+    if interval.state == IntervalState::Idle {
+        push(interval.end, Event::Active);
+    }
+
+    push(interval.end, Event::Disconnected);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::sessionize;
+    use crate::testing::{at, interval};
+
+    #[test]
+    fn import_events_round_trip() {
+        let live = TimeDelta::minutes(1);
+        let intervals = vec![
+            interval(0, 5, IntervalState::Active, Some("firefox")),
+            interval(5, 8, IntervalState::Idle, None),
+            interval(8, 10, IntervalState::Active, Some("zsh")),
+            interval(20, 22, IntervalState::Active, Some("zsh")),
+        ];
+
+        let events = to_events(&intervals, live, &[]);
+        assert_eq!(
+            events.iter().filter(|e| e.event == Event::Alive).count(),
+            8
+        );
+
+        let mut expected = intervals.clone();
+        expected.insert(
+            3,
+            Interval {
+                start: at(11),
+                end: at(20),
+                state: IntervalState::Offline,
+                focused: None,
+            },
+        );
+
+        let sessionized = sessionize(&events, live.to_std().unwrap(), at(30));
+        assert_eq!(&sessionized[..5], &expected[..]);
+        assert_eq!(sessionized[5].state, IntervalState::Offline);
+    }
+
+    #[test]
+    fn import_skips_covered() {
+        let live = TimeDelta::minutes(1);
+        let existing: Vec<_> = [3, 4, 5]
+            .into_iter()
+            .map(|min| TimedEvent {
+                timestamp: at(min),
+                event: Event::Alive,
+            })
+            .collect();
+
+        let covered = covered(&existing, live);
+        assert_eq!(covered, vec![(at(3), at(6))]);
+
+        let imported = interval(0, 10, IntervalState::Active, Some("zsh"));
+        let parts = subtract(&imported, &covered);
+        assert_eq!(
+            parts,
+            vec![
+                interval(0, 3, IntervalState::Active, Some("zsh")),
+                interval(6, 10, IntervalState::Active, Some("zsh")),
+            ]
+        );
+
+        // The part after the existing events resets their state.
+        let seen: Vec<_> = existing.iter().map(|e| e.timestamp).collect();
+        let events = to_events(&parts, live, &seen);
+        let resumed: Vec<_> = events
+            .iter()
+            .filter(|e| e.event == Event::Resumed)
+            .map(|e| e.timestamp)
+            .collect();
+        assert_eq!(resumed, vec![at(6)]);
+    }
+}
@@ -0,0 +1,278 @@
+// https://docs.activitywatch.net/en/latest/buckets-and-events.html
+
+use super::{ImportError, subtract};
+use crate::events::Focused;
+use crate::session::{Interval, IntervalState};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Bucket type of `aw-watcher-window`.
+pub const WINDOW_BUCKET: &str = "currentwindow";
+
+/// Bucket type of `aw-watcher-afk`.
+pub const AFK_BUCKET: &str = "afkstatus";
+
+/// AFK status of the time away from the keyboard.
+pub const AFK: &str = "afk";
+
+/// AFK status of the time at the keyboard.
+pub const NOT_AFK: &str = "not-afk";
+
+/// Buckets as the ActivityWatch web UI exports and aw-server imports them.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Export {
+    pub buckets: BTreeMap<String, Bucket>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Bucket {
+    pub id: String,
+    #[serde(default)]
+    pub created: Option<DateTime<Utc>>,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub client: String,
+    pub hostname: String,
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Event {
+    pub timestamp: DateTime<Utc>,
+    /// In seconds.
+    pub duration: f64,
+    pub data: EventData,
+}
+
+/// Data of the window and AFK events, the rest is ignored.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct EventData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+impl Event {
+    fn end(&self) -> Option<DateTime<Utc>> {
+        let millis = (self.duration * 1000.0).round() as i64;
+        self.timestamp
+            .checked_add_signed(TimeDelta::try_milliseconds(millis)?)
+    }
+}
+
+impl Export {
+    pub fn parse(input: &str) -> Result<Self, ImportError> {
+        Ok(serde_json::from_str(input)?)
+    }
+
+    /// Hosts the buckets were recorded on.
+    pub fn hosts(&self) -> BTreeSet<&str> {
+        self.buckets
+            .values()
+            .map(|bucket| bucket.hostname.as_str())
+            .collect()
+    }
+
+    /// Window and AFK buckets of the host as sorted intervals: the time away
+    /// is idle, the focused window gets the rest. `host` can be left out
+    /// when the export has a single one.
+    pub fn intervals(
+        &self,
+        host: Option<&str>,
+    ) -> Result<Vec<Interval>, ImportError> {
+        let hosts = self.hosts();
+
+        let host = match host {
+            Some(host) if hosts.contains(host) => host,
+            Some(host) => return Err(ImportError::UnknownHost(host.into())),
+            None if hosts.len() > 1 => {
+                return Err(ImportError::Hosts(
+                    hosts.into_iter().map(String::from).collect(),
+                ));
+            }
+            None => match hosts.first() {
+                Some(host) => host,
+                None => return Ok(vec![]),
+            },
+        };
+
+        let windows = self.events(host, WINDOW_BUCKET).filter_map(|event| {
+            Some(Interval {
+                start: event.timestamp,
+                end: event.end()?,
+                state: IntervalState::Active,
+                focused: Some(Focused {
+                    title: event.data.title.clone().unwrap_or_default(),
+                    id: event.data.app.clone().unwrap_or_default(),
+                    ..Default::default()
+                }),
+            })
+        });
+
+        let away = self
+            .events(host, AFK_BUCKET)
+            .filter(|event| event.data.status.as_deref() == Some(AFK))
+            .filter_map(|event| {
+                Some(Interval {
+                    start: event.timestamp,
+                    end: event.end()?,
+                    state: IntervalState::Idle,
+                    focused: None,
+                })
+            });
+
+        let away = untangle(away.collect());
+        let ranges: Vec<_> = away
+            .iter()
+            .map(|interval| (interval.start, interval.end))
+            .collect();
+
+        let mut intervals: Vec<_> = untangle(windows.collect())
+            .iter()
+            .flat_map(|interval| subtract(interval, &ranges))
+            .chain(away)
+            .collect();
+
+        intervals.sort_by_key(|interval| interval.start);
+        Ok(intervals)
+    }
+
+    fn events<'a>(
+        &'a self,
+        host: &'a str,
+        kind: &'a str,
+    ) -> impl Iterator<Item = &'a Event> {
+        self.buckets
+            .values()
+            .filter(move |bucket| {
+                bucket.hostname == host && bucket.kind == kind
+            })
+            .flat_map(|bucket| &bucket.events)
+    }
+}
+
+/// Sorts the intervals and cuts off where they overlap the previous one.
+fn untangle(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    intervals.sort_by_key(|interval| interval.start);
+
+    let mut untangled: Vec<Interval> = vec![];
+
+    for interval in intervals {
+        let start = match untangled.last() {
+            Some(last) => interval.start.max(last.end),
+            None => interval.start,
+        };
+
+        untangled.extend(interval.clip(start, interval.end));
+    }
+
+    untangled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(min: u32, sec: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 10, min, sec).unwrap()
+    }
+
+    const EXPORT: &str = r#"{
+        "buckets": {
+            "aw-watcher-window_laptop": {
+                "id": "aw-watcher-window_laptop",
+                "type": "currentwindow",
+                "client": "aw-watcher-window",
+                "hostname": "laptop",
+                "events": [
+                    {
+                        "timestamp": "2025-01-01T12:03:00+02:00",
+                        "duration": 240.5,
+                        "data": {"app": "foot", "title": "vim"}
+                    },
+                    {
+                        "timestamp": "2025-01-01T10:00:00Z",
+                        "duration": 200,
+                        "data": {"app": "firefox", "title": "docs"}
+                    }
+                ]
+            },
+            "aw-watcher-afk_laptop": {
+                "id": "aw-watcher-afk_laptop",
+                "type": "afkstatus",
+                "client": "aw-watcher-afk",
+                "hostname": "laptop",
+                "events": [
+                    {
+                        "timestamp": "2025-01-01T10:00:00Z",
+                        "duration": 60,
+                        "data": {"status": "not-afk"}
+                    },
+                    {
+                        "timestamp": "2025-01-01T10:01:00Z",
+                        "duration": 60,
+                        "data": {"status": "afk"}
+                    }
+                ]
+            },
+            "aw-watcher-window_desktop": {
+                "id": "aw-watcher-window_desktop",
+                "type": "currentwindow",
+                "hostname": "desktop"
+            }
+        }
+    }"#;
+
+    #[test]
+    fn activitywatch_intervals() -> Result<(), ImportError> {
+        let export = Export::parse(EXPORT)?;
+        assert_eq!(
+            export.hosts().into_iter().collect::<Vec<_>>(),
+            ["desktop", "laptop"]
+        );
+
+        assert!(matches!(
+            export.intervals(None),
+            Err(ImportError::Hosts(hosts)) if hosts.len() == 2
+        ));
+        assert!(matches!(
+            export.intervals(Some("phone")),
+            Err(ImportError::UnknownHost(_))
+        ));
+        assert!(export.intervals(Some("desktop"))?.is_empty());
+
+        let intervals = export.intervals(Some("laptop"))?;
+        let spans: Vec<_> = intervals
+            .iter()
+            .map(|i| (i.start, i.end, i.state, i.focused.as_ref()))
+            .map(|(start, end, state, focused)| {
+                (start, end, state, focused.map(|f| f.id.as_str()))
+            })
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![
+                (at(0, 0), at(1, 0), IntervalState::Active, Some("firefox")),
+                (at(1, 0), at(2, 0), IntervalState::Idle, None),
+                (at(2, 0), at(3, 20), IntervalState::Active, Some("firefox")),
+                // Starts where the overlapping window ends.
+                (
+                    at(3, 20),
+                    at(7, 0) + TimeDelta::milliseconds(500),
+                    IntervalState::Active,
+                    Some("foot")
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -1,8 +1,15 @@
 pub const NAME: &str = "matiane";
 
+pub mod category;
 pub mod config;
 pub mod events;
+pub mod export;
+pub mod import;
 pub mod log;
 pub mod process;
+pub mod session;
 pub mod store;
+pub mod summary;
+#[cfg(test)]
+mod testing;
 pub mod xdg;
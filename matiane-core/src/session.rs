@@ -0,0 +1,864 @@
+use crate::events::{Event, Focused, TimedEvent};
+use crate::store::{EventReader, StoreError};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Extra time on top of the live interval before a silence between two
+/// events is treated as a crash or a power loss.
+pub const LIVE_GRACE: TimeDelta = TimeDelta::seconds(5);
+
+/// How far before the requested range to start reading, so the window
+/// focused before the range start is known.
+pub const LOOKBACK: TimeDelta = TimeDelta::days(1);
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IntervalState {
+    /// User is working in the focused window.
+    #[default]
+    Active,
+    /// swayidle: No input for the idle timeout.
+    Idle,
+    /// No input either, but an idle inhibitor or a fullscreen window says
+    /// the user is still watching the focused window.
+    Passive,
+    /// Screen is locked or asleep.
+    Asleep,
+    /// Tracking was paused by the user.
+    Paused,
+    /// Nothing was logged, the daemon was down or the machine lost power.
+    Offline,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Interval {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub state: IntervalState,
+    /// Focused window, only set for the active and passive intervals.
+    pub focused: Option<Focused>,
+}
+
+impl Interval {
+    pub fn duration(&self) -> TimeDelta {
+        self.end - self.start
+    }
+
+    /// Part of the interval within `[from, to)`, if any.
+    pub fn clip(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<Self> {
+        let start = self.start.max(from);
+        let end = self.end.min(to);
+
+        if start >= end {
+            return None;
+        }
+
+        Some(Interval {
+            start,
+            end,
+            state: self.state,
+            focused: self.focused.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct OpenInterval {
+    start: DateTime<Utc>,
+    state: IntervalState,
+    focused: Option<Focused>,
+}
+
+/// Folds the point events from the log into closed intervals.
+///
+/// Events are expected in chronological order, older events than the last
+/// seen one are skipped.
+#[derive(Debug)]
+pub struct Sessionizer {
+    live_interval: TimeDelta,
+    state: IntervalState,
+    focused: Option<Focused>,
+    open: Option<OpenInterval>,
+    last_seen: Option<DateTime<Utc>>,
+}
+
+impl Sessionizer {
+    pub fn new(live_interval: Duration) -> Self {
+        Sessionizer {
+            live_interval: TimeDelta::from_std(live_interval)
+                .unwrap_or(TimeDelta::MAX),
+            state: IntervalState::Active,
+            focused: None,
+            open: None,
+            last_seen: None,
+        }
+    }
+
+    /// Feeds the next event, returns the intervals it has closed.
+    pub fn push(&mut self, event: &TimedEvent) -> Vec<Interval> {
+        let at = event.timestamp;
+        let mut closed = vec![];
+
+        if let Some(last_seen) = self.last_seen {
+            if at < last_seen {
+                warn!("Skipping out of order event at {}.", at);
+                return closed;
+            }
+
+            if self.is_gap(last_seen, at) {
+                self.offline(last_seen, at, &mut closed);
+            }
+        }
+
+        self.last_seen = Some(at);
+
+        match &event.event {
+            Event::Focused(focused) => {
+                let same = self.focused.as_ref() == Some(focused.as_ref());
+                self.focused = Some(focused.as_ref().clone());
+
+                if self.is_present() && !(same && self.open.is_some()) {
+                    self.transition(at, self.state, &mut closed);
+                }
+            }
+            Event::Alive | Event::Annotation(_) => {}
+            Event::Idle => {
+                if self.state == IntervalState::Active {
+                    self.transition(at, IntervalState::Idle, &mut closed);
+                }
+            }
+            Event::Passive => {
+                if self.state == IntervalState::Active {
+                    self.transition(at, IntervalState::Passive, &mut closed);
+                }
+            }
+            Event::Active => {
+                if matches!(
+                    self.state,
+                    IntervalState::Idle | IntervalState::Passive
+                ) {
+                    self.transition(at, IntervalState::Active, &mut closed);
+                }
+            }
+            Event::Paused => {
+                if self.state != IntervalState::Paused {
+                    self.transition(at, IntervalState::Paused, &mut closed);
+                }
+            }
+            Event::Resumed => {
+                if self.state == IntervalState::Paused {
+                    self.transition(at, IntervalState::Active, &mut closed);
+                }
+            }
+            Event::Sleep => {
+                if self.state != IntervalState::Asleep {
+                    self.transition(at, IntervalState::Asleep, &mut closed);
+                }
+            }
+            Event::Awake => {
+                if self.state == IntervalState::Asleep {
+                    self.transition(at, IntervalState::Active, &mut closed);
+                }
+            }
+            Event::Disconnected | Event::Private => {
+                self.focused = None;
+
+                if self.is_present() {
+                    self.close(at, &mut closed);
+                }
+            }
+        }
+
+        closed
+    }
+
+    /// Closes whatever is still open at `at`. If the log went silent
+    /// before that, the rest is reported as offline.
+    pub fn finish(mut self, at: DateTime<Utc>) -> Vec<Interval> {
+        let mut closed = vec![];
+
+        match self.last_seen {
+            Some(last_seen) if self.is_gap(last_seen, at) => {
+                self.offline(last_seen, at, &mut closed);
+            }
+            _ => self.close(at, &mut closed),
+        }
+
+        closed
+    }
+
+    /// State the next interval opens in.
+    pub fn state(&self) -> IntervalState {
+        self.state
+    }
+
+    /// Interval that is still open, as if it was closed at `at`.
+    pub fn current(&self, at: DateTime<Utc>) -> Option<Interval> {
+        let open = self.open.as_ref()?;
+
+        let end = match self.last_seen {
+            Some(last_seen) if self.is_gap(last_seen, at) => {
+                last_seen + self.live_interval
+            }
+            _ => at,
+        };
+
+        Some(Interval {
+            start: open.start,
+            end: end.max(open.start),
+            state: open.state,
+            focused: open.focused.clone(),
+        })
+    }
+
+    /// Credits the open interval up to one live interval after the last
+    /// heartbeat, the rest until `at` is unknown. A pause ends here too, the
+    /// daemon starts unpaused.
+    fn offline(
+        &mut self,
+        last_seen: DateTime<Utc>,
+        at: DateTime<Utc>,
+        closed: &mut Vec<Interval>,
+    ) {
+        let cap = last_seen + self.live_interval;
+
+        self.close(cap, closed);
+        self.state = IntervalState::Active;
+        self.focused = None;
+
+        if at > cap {
+            closed.push(Interval {
+                start: cap,
+                end: at,
+                state: IntervalState::Offline,
+                focused: None,
+            });
+        }
+    }
+
+    /// Time goes to the focused window.
+    fn is_present(&self) -> bool {
+        matches!(self.state, IntervalState::Active | IntervalState::Passive)
+    }
+
+    fn is_gap(&self, last_seen: DateTime<Utc>, at: DateTime<Utc>) -> bool {
+        // Heartbeats stop while the machine sleeps.
+        if self.state == IntervalState::Asleep {
+            return false;
+        }
+
+        at - last_seen > self.live_interval + LIVE_GRACE
+    }
+
+    fn transition(
+        &mut self,
+        at: DateTime<Utc>,
+        state: IntervalState,
+        closed: &mut Vec<Interval>,
+    ) {
+        self.close(at, closed);
+        self.state = state;
+
+        let focused = match state {
+            IntervalState::Active | IntervalState::Passive => {
+                match &self.focused {
+                    Some(focused) => Some(focused.clone()),
+                    // Nothing to attribute the time to yet.
+                    None => return,
+                }
+            }
+            _ => None,
+        };
+
+        self.open = Some(OpenInterval {
+            start: at,
+            state,
+            focused,
+        });
+    }
+
+    fn close(&mut self, at: DateTime<Utc>, closed: &mut Vec<Interval>) {
+        let Some(open) = self.open.take() else {
+            return;
+        };
+
+        if at <= open.start {
+            return;
+        }
+
+        closed.push(Interval {
+            start: open.start,
+            end: at,
+            state: open.state,
+            focused: open.focused,
+        });
+    }
+}
+
+/// Counts the passive presence as active time.
+pub fn passive_as_active(intervals: &mut [Interval]) {
+    for interval in intervals {
+        if interval.state == IntervalState::Passive {
+            interval.state = IntervalState::Active;
+        }
+    }
+}
+
+/// Local midnight to midnight.
+pub fn local_day(date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let local = |date: NaiveDate| {
+        let midnight = date.and_time(NaiveTime::MIN);
+        Local
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map(|start| start.to_utc())
+            .unwrap_or_else(|| midnight.and_utc())
+    };
+
+    (local(date), local(date + TimeDelta::days(1)))
+}
+
+/// Sessionizes the whole list of events, closing the last interval at `end`.
+pub fn sessionize<'a>(
+    events: impl IntoIterator<Item = &'a TimedEvent>,
+    live_interval: Duration,
+    end: DateTime<Utc>,
+) -> Vec<Interval> {
+    let mut sessionizer = Sessionizer::new(live_interval);
+    let mut intervals = vec![];
+
+    for event in events {
+        intervals.extend(sessionizer.push(event));
+    }
+
+    intervals.extend(sessionizer.finish(end));
+    intervals
+}
+
+/// Reads the logs and returns the intervals clipped to `[from, to)`.
+///
+/// Malformed lines are logged and skipped. The still open interval is closed
+/// at `to`, or now, whichever is earlier.
+pub async fn read_intervals(
+    dir: PathBuf,
+    live_interval: Duration,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<Interval>, StoreError> {
+    let mut reader = EventReader::open(dir, from - LOOKBACK, to).await?;
+    let mut sessionizer = Sessionizer::new(live_interval);
+    let mut intervals = vec![];
+
+    loop {
+        match reader.next().await {
+            Ok(Some(event)) => intervals.extend(sessionizer.push(&event)),
+            Ok(None) => break,
+            Err(err @ StoreError::MalformedLine { .. }) => {
+                warn!("Skipping: {}", err);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    intervals.extend(sessionizer.finish(to.min(Utc::now())));
+
+    Ok(intervals
+        .iter()
+        .filter_map(|interval| interval.clip(from, to))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const LIVE: Duration = Duration::from_secs(60);
+
+    fn at(min: u32, sec: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 10, min, sec).unwrap()
+    }
+
+    fn focused(id: &str, title: &str) -> Focused {
+        Focused {
+            title: title.into(),
+            id: id.into(),
+            pid: 1,
+            ..Default::default()
+        }
+    }
+
+    fn ev(time: DateTime<Utc>, event: Event) -> TimedEvent {
+        TimedEvent {
+            timestamp: time,
+            event,
+        }
+    }
+
+    fn focus(time: DateTime<Utc>, id: &str, title: &str) -> TimedEvent {
+        ev(time, Event::Focused(Box::new(focused(id, title))))
+    }
+
+    fn active(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        id: &str,
+        title: &str,
+    ) -> Interval {
+        Interval {
+            start,
+            end,
+            state: IntervalState::Active,
+            focused: Some(focused(id, title)),
+        }
+    }
+
+    fn other(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        state: IntervalState,
+    ) -> Interval {
+        Interval {
+            start,
+            end,
+            state,
+            focused: None,
+        }
+    }
+
+    #[test]
+    fn sessionize_empty() {
+        assert!(sessionize(&[], LIVE, at(10, 0)).is_empty());
+    }
+
+    #[test]
+    fn sessionize_focus_changes() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            focus(at(0, 30), "firefox", "News"),
+            ev(at(0, 50), Event::Alive),
+            focus(at(1, 0), "Alacritty", "zsh"),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(1, 40));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(0, 30), "firefox", "Mail"),
+                active(at(0, 30), at(1, 0), "firefox", "News"),
+                active(at(1, 0), at(1, 40), "Alacritty", "zsh"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_same_window_refocus_is_merged() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            focus(at(0, 10), "firefox", "Mail"),
+            focus(at(0, 20), "firefox", "Mail"),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(0, 30));
+
+        assert_eq!(
+            intervals,
+            vec![active(at(0, 0), at(0, 30), "firefox", "Mail")]
+        );
+    }
+
+    #[test]
+    fn sessionize_disconnected() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(0, 30), Event::Disconnected),
+            ev(at(1, 0), Event::Alive),
+            focus(at(1, 10), "firefox", "Mail"),
+            ev(at(1, 20), Event::Idle),
+            ev(at(1, 30), Event::Disconnected),
+            // Unknown focus after becoming active again.
+            ev(at(1, 40), Event::Active),
+            focus(at(1, 50), "Alacritty", "zsh"),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(2, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(0, 30), "firefox", "Mail"),
+                active(at(1, 10), at(1, 20), "firefox", "Mail"),
+                other(at(1, 20), at(1, 40), IntervalState::Idle),
+                active(at(1, 50), at(2, 0), "Alacritty", "zsh"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_private() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(0, 30), Event::Private),
+            ev(at(1, 0), Event::Alive),
+            focus(at(1, 10), "firefox", "Mail"),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(2, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(0, 30), "firefox", "Mail"),
+                active(at(1, 10), at(2, 0), "firefox", "Mail"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_idle() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(1, 0), Event::Idle),
+            ev(at(1, 30), Event::Alive),
+            // focus changes while idle are kept for later.
+            focus(at(1, 40), "Alacritty", "zsh"),
+            ev(at(2, 0), Event::Active),
+            ev(at(2, 10), Event::Active),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(3, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(1, 0), "firefox", "Mail"),
+                other(at(1, 0), at(2, 0), IntervalState::Idle),
+                active(at(2, 0), at(3, 0), "Alacritty", "zsh"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_passive() {
+        let events = [
+            focus(at(0, 0), "mpv", "film"),
+            ev(at(1, 0), Event::Passive),
+            // Next episode.
+            focus(at(1, 30), "mpv", "film 2"),
+            ev(at(2, 0), Event::Active),
+            ev(at(2, 30), Event::Passive),
+            ev(at(2, 40), Event::Disconnected),
+        ];
+
+        let mut intervals = sessionize(&events, LIVE, at(3, 0));
+        let passive = |start, end, title| Interval {
+            state: IntervalState::Passive,
+            ..active(start, end, "mpv", title)
+        };
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(1, 0), "mpv", "film"),
+                passive(at(1, 0), at(1, 30), "film"),
+                passive(at(1, 30), at(2, 0), "film 2"),
+                active(at(2, 0), at(2, 30), "mpv", "film 2"),
+                passive(at(2, 30), at(2, 40), "film 2"),
+            ]
+        );
+
+        passive_as_active(&mut intervals);
+
+        assert!(
+            intervals
+                .iter()
+                .all(|interval| interval.state == IntervalState::Active)
+        );
+    }
+
+    #[test]
+    fn sessionize_paused() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(1, 0), Event::Paused),
+            ev(at(2, 0), Event::Alive),
+            // Focus changes while paused are not tracked.
+            focus(at(2, 30), "Alacritty", "zsh"),
+            ev(at(3, 0), Event::Resumed),
+            focus(at(3, 10), "firefox", "Mail"),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(4, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(1, 0), "firefox", "Mail"),
+                other(at(1, 0), at(3, 0), IntervalState::Paused),
+                active(at(3, 0), at(3, 10), "Alacritty", "zsh"),
+                active(at(3, 10), at(4, 0), "firefox", "Mail"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_pause_ends_offline() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(1, 0), Event::Paused),
+            ev(at(2, 0), Event::Alive),
+            // Daemon restarted or the machine rebooted, it starts unpaused.
+            focus(at(10, 0), "Alacritty", "zsh"),
+            ev(at(11, 0), Event::Alive),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(12, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(1, 0), "firefox", "Mail"),
+                other(at(1, 0), at(3, 0), IntervalState::Paused),
+                other(at(3, 0), at(10, 0), IntervalState::Offline),
+                active(at(10, 0), at(12, 0), "Alacritty", "zsh"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_sleep() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(0, 30), Event::Idle),
+            ev(at(1, 0), Event::Sleep),
+            // resume from idle while asleep is ignored.
+            ev(at(20, 0), Event::Active),
+            ev(at(30, 0), Event::Awake),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(31, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(0, 30), "firefox", "Mail"),
+                other(at(0, 30), at(1, 0), IntervalState::Idle),
+                other(at(1, 0), at(30, 0), IntervalState::Asleep),
+                active(at(30, 0), at(31, 0), "firefox", "Mail"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_no_focus_yet() {
+        let events = [
+            ev(at(0, 0), Event::Alive),
+            ev(at(0, 10), Event::Idle),
+            ev(at(0, 20), Event::Active),
+            focus(at(0, 30), "firefox", "Mail"),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(0, 40));
+
+        assert_eq!(
+            intervals,
+            vec![
+                other(at(0, 10), at(0, 20), IntervalState::Idle),
+                active(at(0, 30), at(0, 40), "firefox", "Mail"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_heartbeat_gap() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(1, 0), Event::Alive),
+            ev(at(2, 0), Event::Alive),
+            // daemon was killed, restarted 10 minutes later.
+            ev(at(12, 0), Event::Alive),
+            focus(at(13, 0), "Alacritty", "zsh"),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(14, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(3, 0), "firefox", "Mail"),
+                other(at(3, 0), at(12, 0), IntervalState::Offline),
+                active(at(13, 0), at(14, 0), "Alacritty", "zsh"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_heartbeat_within_grace() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(1, 3), Event::Alive),
+            ev(at(2, 6), Event::Alive),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(3, 0));
+
+        assert_eq!(
+            intervals,
+            vec![active(at(0, 0), at(3, 0), "firefox", "Mail")]
+        );
+    }
+
+    #[test]
+    fn sessionize_idle_gap() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(1, 0), Event::Idle),
+            // power loss while idle, back after boot.
+            ev(at(40, 0), Event::Active),
+            focus(at(40, 10), "Alacritty", "zsh"),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(41, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(1, 0), "firefox", "Mail"),
+                other(at(1, 0), at(2, 0), IntervalState::Idle),
+                other(at(2, 0), at(40, 0), IntervalState::Offline),
+                active(at(40, 10), at(41, 0), "Alacritty", "zsh"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_finish_after_silence() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(1, 0), Event::Alive),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(30, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(2, 0), "firefox", "Mail"),
+                other(at(2, 0), at(30, 0), IntervalState::Offline),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_gap_without_open_interval() {
+        let events = [
+            ev(at(0, 0), Event::Alive),
+            ev(at(5, 0), Event::Alive),
+            focus(at(5, 10), "firefox", "Mail"),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(6, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                other(at(1, 0), at(5, 0), IntervalState::Offline),
+                active(at(5, 10), at(6, 0), "firefox", "Mail"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_long_sleep_is_not_offline() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            ev(at(1, 0), Event::Sleep),
+            ev(at(50, 0), Event::Awake),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(51, 0));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(1, 0), "firefox", "Mail"),
+                other(at(1, 0), at(50, 0), IntervalState::Asleep),
+                active(at(50, 0), at(51, 0), "firefox", "Mail"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessionize_out_of_order() {
+        let events = [
+            focus(at(0, 0), "firefox", "Mail"),
+            focus(at(0, 30), "Alacritty", "zsh"),
+            focus(at(0, 20), "firefox", "News"),
+        ];
+
+        let intervals = sessionize(&events, LIVE, at(0, 40));
+
+        assert_eq!(
+            intervals,
+            vec![
+                active(at(0, 0), at(0, 30), "firefox", "Mail"),
+                active(at(0, 30), at(0, 40), "Alacritty", "zsh"),
+            ]
+        );
+    }
+
+    #[test]
+    fn interval_clip() {
+        let interval = active(at(1, 0), at(3, 0), "firefox", "Mail");
+
+        assert_eq!(interval.clip(at(0, 0), at(10, 0)), Some(interval.clone()));
+        assert_eq!(
+            interval.clip(at(2, 0), at(10, 0)),
+            Some(active(at(2, 0), at(3, 0), "firefox", "Mail"))
+        );
+        assert_eq!(
+            interval.clip(at(0, 0), at(1, 30)),
+            Some(active(at(1, 0), at(1, 30), "firefox", "Mail"))
+        );
+        assert_eq!(interval.clip(at(3, 0), at(10, 0)), None);
+        assert_eq!(interval.clip(at(0, 0), at(1, 0)), None);
+    }
+
+    #[test]
+    fn sessionizer_current() {
+        let mut sessionizer = Sessionizer::new(LIVE);
+
+        assert!(sessionizer.current(at(0, 0)).is_none());
+
+        let closed = sessionizer.push(&focus(at(0, 0), "firefox", "Mail"));
+        assert!(closed.is_empty());
+
+        assert_eq!(
+            sessionizer.current(at(0, 15)),
+            Some(active(at(0, 0), at(0, 15), "firefox", "Mail"))
+        );
+
+        let closed = sessionizer.push(&ev(at(0, 20), Event::Idle));
+        assert_eq!(
+            closed,
+            vec![active(at(0, 0), at(0, 20), "firefox", "Mail")]
+        );
+
+        assert_eq!(
+            sessionizer.current(at(0, 25)),
+            Some(other(at(0, 20), at(0, 25), IntervalState::Idle))
+        );
+
+        // Capped when the heartbeats stop.
+        assert_eq!(
+            sessionizer.current(at(10, 0)),
+            Some(other(at(0, 20), at(1, 20), IntervalState::Idle))
+        );
+    }
+}
@@ -2,12 +2,18 @@ use thiserror::Error;
 
 use super::events::TimedEvent;
 use chrono::{DateTime, NaiveDate, Utc};
+use futures::Stream;
 use log::error;
 use serde_json;
-use std::path::PathBuf;
+use std::collections::VecDeque;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{
+    AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader,
+    Lines,
+};
 
 #[derive(Debug, Error)]
 pub enum StoreError {
@@ -15,6 +21,12 @@ pub enum StoreError {
     Io(#[from] std::io::Error),
     #[error("Store failed to encode event")]
     EncodeError(#[from] serde_json::Error),
+    #[error("Malformed line {line} in {path:?}: {source}")]
+    MalformedLine {
+        path: PathBuf,
+        line: usize,
+        source: serde_json::Error,
+    },
 }
 
 pub struct EventWriter {
@@ -98,6 +110,218 @@ async fn open_write_file(filepath: PathBuf) -> Result<File, StoreError> {
         .map_err(StoreError::Io)
 }
 
+struct OpenedLog {
+    path: PathBuf,
+    lines: Lines<BufReader<File>>,
+    line: usize,
+}
+
+/// Reads events back from the daily log files in `[from, to)` range.
+///
+/// Malformed lines are returned as [`StoreError::MalformedLine`] and the
+/// reader carries on with the next line on the following call.
+pub struct EventReader {
+    dir: PathBuf,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    dates: VecDeque<NaiveDate>,
+    current: Option<OpenedLog>,
+}
+
+impl EventReader {
+    pub async fn open(
+        dir: PathBuf,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Self, StoreError> {
+        let (first, last) = (from.date_naive(), to.date_naive());
+        let dates = list_dates(&dir)
+            .await?
+            .into_iter()
+            .filter(|date| *date >= first && *date <= last)
+            .collect();
+
+        Ok(EventReader {
+            dir,
+            from,
+            to,
+            dates,
+            current: None,
+        })
+    }
+
+    /// Returns the next event in range, `Ok(None)` once all files are read.
+    pub async fn next(&mut self) -> Result<Option<TimedEvent>, StoreError> {
+        loop {
+            let opened = match self.current.as_mut() {
+                Some(opened) => opened,
+                None => {
+                    let Some(date) = self.dates.pop_front() else {
+                        return Ok(None);
+                    };
+
+                    let path = self.dir.join(get_filename_by_date(date));
+                    log::debug!("opening log file for reading: {:?}", path);
+
+                    let file = match File::open(&path).await {
+                        Ok(file) => file,
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                            continue;
+                        }
+                        Err(e) => return Err(StoreError::Io(e)),
+                    };
+
+                    self.current.insert(OpenedLog {
+                        path,
+                        lines: BufReader::new(file).lines(),
+                        line: 0,
+                    })
+                }
+            };
+
+            let line = match opened.lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => {
+                    self.current = None;
+                    continue;
+                }
+                Err(e) => {
+                    self.current = None;
+                    return Err(StoreError::Io(e));
+                }
+            };
+
+            opened.line += 1;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let event: TimedEvent = match serde_json::from_str(&line) {
+                Ok(event) => event,
+                Err(source) => {
+                    return Err(StoreError::MalformedLine {
+                        path: opened.path.clone(),
+                        line: opened.line,
+                        source,
+                    });
+                }
+            };
+
+            if event.timestamp >= self.from && event.timestamp < self.to {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    pub fn into_stream(
+        self,
+    ) -> impl Stream<Item = Result<TimedEvent, StoreError>> {
+        futures::stream::unfold(self, |mut reader| async move {
+            match reader.next().await {
+                Ok(Some(event)) => Some((Ok(event), reader)),
+                Ok(None) => None,
+                Err(err) => Some((Err(err), reader)),
+            }
+        })
+    }
+}
+
+/// Lists the dates of all log files in the directory, sorted.
+pub async fn list_dates(
+    dir: impl AsRef<Path>,
+) -> Result<Vec<NaiveDate>, StoreError> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(vec![]);
+        }
+        Err(e) => return Err(StoreError::Io(e)),
+    };
+
+    let mut dates = vec![];
+
+    while let Some(entry) = entries.next_entry().await? {
+        if let Some(date) = get_date_by_filename(&entry.path()) {
+            dates.push(date);
+        }
+    }
+
+    dates.sort();
+
+    Ok(dates)
+}
+
+/// How much of the log end is read at a time, looking for its last event.
+const TAIL_CHUNK: u64 = 4096;
+
+/// Timestamps of the first and last event in the log file of the date, read
+/// off the ends of the file. Lines that fail to decode are skipped, `None`
+/// if there is no file or no event in it.
+pub async fn read_bounds(
+    dir: impl AsRef<Path>,
+    date: NaiveDate,
+) -> Result<Option<(DateTime<Utc>, DateTime<Utc>)>, StoreError> {
+    let path = dir.as_ref().join(get_filename_by_date(date));
+
+    let mut file = match File::open(&path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(None);
+        }
+        Err(e) => return Err(StoreError::Io(e)),
+    };
+
+    let mut first = None;
+    let mut lines = BufReader::new(&mut file).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if let Some(event) = decode(line.as_bytes()) {
+            first = Some(event.timestamp);
+            break;
+        }
+    }
+
+    let Some(first) = first else {
+        return Ok(None);
+    };
+
+    let mut end = file.seek(SeekFrom::End(0)).await?;
+    let mut tail = vec![];
+
+    while end > 0 {
+        let start = end.saturating_sub(TAIL_CHUNK);
+        let mut chunk = vec![0; (end - start) as usize];
+
+        file.seek(SeekFrom::Start(start)).await?;
+        file.read_exact(&mut chunk).await?;
+        chunk.append(&mut tail);
+        tail = chunk;
+        end = start;
+
+        // The line before the first newline may begin in the previous chunk.
+        let whole = match tail.iter().position(|b| *b == b'\n') {
+            _ if start == 0 => 0,
+            Some(newline) => newline + 1,
+            None => continue,
+        };
+
+        for line in tail[whole..].rsplit(|b| *b == b'\n') {
+            if let Some(event) = decode(line) {
+                return Ok(Some((first, event.timestamp)));
+            }
+        }
+
+        tail.truncate(whole);
+    }
+
+    Ok(Some((first, first)))
+}
+
+fn decode(line: &[u8]) -> Option<TimedEvent> {
+    serde_json::from_slice(line.trim_ascii()).ok()
+}
+
 pub const LOCK_FILE_TIME_SEC: Duration = Duration::from_secs(60);
 
 #[derive(Debug)]
@@ -154,10 +378,24 @@ pub async fn acquire_lock_file(
     Ok(lock)
 }
 
-fn get_filename_by_date(date: NaiveDate) -> PathBuf {
+pub(crate) fn get_filename_by_date(date: NaiveDate) -> PathBuf {
     PathBuf::from(date.format("%Y%m%d").to_string()).with_extension("log")
 }
 
+fn get_date_by_filename(path: &Path) -> Option<NaiveDate> {
+    if path.extension()? != "log" {
+        return None;
+    }
+
+    let stem = path.file_stem()?.to_str()?;
+
+    if stem.len() != 8 {
+        return None;
+    }
+
+    NaiveDate::parse_from_str(stem, "%Y%m%d").ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,4 +439,25 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn get_date_by_filename_test() -> Result<()> {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+
+        let cases: [(&str, Option<NaiveDate>); 7] = [
+            ("19700101.log", date(1970, 1, 1)),
+            ("/some/dir/20251231.log", date(2025, 12, 31)),
+            ("20251231.txt", None),
+            ("20251232.log", None),
+            ("2025123.log", None),
+            ("LOCK", None),
+            ("log", None),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(get_date_by_filename(Path::new(name)), expected);
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,367 @@
+use crate::category::Rules;
+use crate::session::{Interval, IntervalState};
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Group of the windows recorded without a workspace.
+pub const UNKNOWN_WORKSPACE: &str = "Unknown workspace";
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    /// Application id, with the window titles underneath.
+    #[default]
+    App,
+    /// Window title.
+    Title,
+    /// Category from the rules, with the application ids underneath.
+    Category,
+    /// Workspace name, with the application ids underneath.
+    Workspace,
+}
+
+impl GroupBy {
+    pub const ALL: [GroupBy; 4] = [
+        GroupBy::App,
+        GroupBy::Title,
+        GroupBy::Category,
+        GroupBy::Workspace,
+    ];
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupBy::App => write!(f, "Application"),
+            GroupBy::Title => write!(f, "Title"),
+            GroupBy::Category => write!(f, "Category"),
+            GroupBy::Workspace => write!(f, "Workspace"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Total {
+    pub name: String,
+    #[serde(serialize_with = "serialize_seconds")]
+    pub duration: TimeDelta,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Total>,
+}
+
+impl Total {
+    /// Share of the `total` in percent.
+    pub fn percent(&self, total: TimeDelta) -> f64 {
+        if total <= TimeDelta::zero() {
+            return 0.0;
+        }
+
+        self.duration.num_milliseconds() as f64 * 100.0
+            / total.num_milliseconds() as f64
+    }
+}
+
+/// Active time totals, sorted by the longest first.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Summary {
+    #[serde(serialize_with = "serialize_seconds")]
+    pub total: TimeDelta,
+    /// Passive presence, not part of the total or the groups.
+    #[serde(serialize_with = "serialize_seconds")]
+    pub passive: TimeDelta,
+    pub groups: Vec<Total>,
+}
+
+pub fn summarize(
+    intervals: &[Interval],
+    group_by: GroupBy,
+    rules: &Rules,
+) -> Summary {
+    let mut groups: HashMap<&str, (TimeDelta, HashMap<&str, TimeDelta>)> =
+        HashMap::new();
+    let mut total = TimeDelta::zero();
+    let mut passive = TimeDelta::zero();
+
+    for interval in intervals {
+        if interval.state == IntervalState::Passive {
+            passive += interval.duration();
+            continue;
+        }
+
+        if interval.state != IntervalState::Active {
+            continue;
+        }
+
+        let Some(focused) = &interval.focused else {
+            continue;
+        };
+
+        let duration = interval.duration();
+        let (key, child) = match group_by {
+            GroupBy::App => (focused.id.as_str(), Some(focused.title.as_str())),
+            GroupBy::Title => (focused.title.as_str(), None),
+            GroupBy::Category => {
+                (rules.category(focused), Some(focused.id.as_str()))
+            }
+            GroupBy::Workspace => (
+                focused.workspace.as_deref().unwrap_or(UNKNOWN_WORKSPACE),
+                Some(focused.id.as_str()),
+            ),
+        };
+
+        let (group_total, children) = groups.entry(key).or_default();
+        *group_total += duration;
+        total += duration;
+
+        if let Some(child) = child {
+            *children.entry(child).or_default() += duration;
+        }
+    }
+
+    let groups = groups
+        .into_iter()
+        .map(|(name, (duration, children))| Total {
+            name: name.into(),
+            duration,
+            children: sorted(
+                children
+                    .into_iter()
+                    .map(|(name, duration)| Total {
+                        name: name.into(),
+                        duration,
+                        children: vec![],
+                    })
+                    .collect(),
+            ),
+        })
+        .collect();
+
+    Summary {
+        total,
+        passive,
+        groups: sorted(groups),
+    }
+}
+
+fn sorted(mut totals: Vec<Total>) -> Vec<Total> {
+    totals.sort_by(|a, b| {
+        b.duration
+            .cmp(&a.duration)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    totals
+}
+
+/// Formats as `1h 05m 09s`, hours are omitted when zero.
+pub fn format_duration(duration: TimeDelta) -> String {
+    let secs = duration.num_seconds().max(0);
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else {
+        format!("{}m {:02}s", minutes, seconds)
+    }
+}
+
+fn serialize_seconds<S>(duration: &TimeDelta, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    s.serialize_i64(duration.num_seconds())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::window_interval;
+
+    fn intervals() -> Vec<Interval> {
+        vec![
+            window_interval(
+                0,
+                10,
+                IntervalState::Active,
+                Some(("firefox", "Mail")),
+            ),
+            window_interval(
+                10,
+                15,
+                IntervalState::Active,
+                Some(("zsh", "vim")),
+            ),
+            window_interval(15, 30, IntervalState::Idle, None),
+            window_interval(
+                30,
+                40,
+                IntervalState::Active,
+                Some(("firefox", "News")),
+            ),
+            window_interval(
+                40,
+                45,
+                IntervalState::Active,
+                Some(("firefox", "Mail")),
+            ),
+            window_interval(
+                45,
+                50,
+                IntervalState::Active,
+                Some(("emacs", "vim")),
+            ),
+            window_interval(50, 59, IntervalState::Offline, None),
+        ]
+    }
+
+    fn total(name: &str, minutes: i64, children: Vec<Total>) -> Total {
+        Total {
+            name: name.into(),
+            duration: TimeDelta::minutes(minutes),
+            children,
+        }
+    }
+
+    #[test]
+    fn summarize_by_app() {
+        let summary = summarize(&intervals(), GroupBy::App, &Rules::default());
+
+        assert_eq!(summary.total, TimeDelta::minutes(35));
+        assert_eq!(
+            summary.groups,
+            vec![
+                total(
+                    "firefox",
+                    25,
+                    vec![total("Mail", 15, vec![]), total("News", 10, vec![])]
+                ),
+                total("emacs", 5, vec![total("vim", 5, vec![])]),
+                total("zsh", 5, vec![total("vim", 5, vec![])]),
+            ]
+        );
+    }
+
+    #[test]
+    fn summarize_by_title() {
+        let summary =
+            summarize(&intervals(), GroupBy::Title, &Rules::default());
+
+        assert_eq!(summary.total, TimeDelta::minutes(35));
+        assert_eq!(
+            summary.groups,
+            vec![
+                total("Mail", 15, vec![]),
+                total("News", 10, vec![]),
+                total("vim", 10, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn summarize_by_category() -> anyhow::Result<()> {
+        let config = toml::from_str(
+            r#"
+            [[rules]]
+            category = "Shell"
+            title = "^vim$"
+            "#,
+        )?;
+        let rules = Rules::new(&config)?;
+        let summary = summarize(&intervals(), GroupBy::Category, &rules);
+
+        assert_eq!(summary.total, TimeDelta::minutes(35));
+        assert_eq!(
+            summary.groups,
+            vec![
+                total("Uncategorized", 25, vec![total("firefox", 25, vec![])]),
+                total(
+                    "Shell",
+                    10,
+                    vec![total("emacs", 5, vec![]), total("zsh", 5, vec![])]
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn summarize_by_workspace() {
+        let mut intervals = intervals();
+        for interval in &mut intervals[..3] {
+            if let Some(focused) = &mut interval.focused {
+                focused.workspace = Some("2: mail".into());
+            }
+        }
+
+        let summary =
+            summarize(&intervals, GroupBy::Workspace, &Rules::default());
+
+        assert_eq!(
+            summary.groups,
+            vec![
+                total(
+                    UNKNOWN_WORKSPACE,
+                    20,
+                    vec![
+                        total("firefox", 15, vec![]),
+                        total("emacs", 5, vec![])
+                    ]
+                ),
+                total(
+                    "2: mail",
+                    15,
+                    vec![total("firefox", 10, vec![]), total("zsh", 5, vec![])]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn summarize_empty() {
+        let summary = summarize(&[], GroupBy::App, &Rules::default());
+
+        assert_eq!(summary, Summary::default());
+    }
+
+    #[test]
+    fn summarize_passive() {
+        let mut intervals = intervals();
+        intervals[2] = window_interval(
+            15,
+            30,
+            IntervalState::Passive,
+            Some(("mpv", "film")),
+        );
+
+        let summary = summarize(&intervals, GroupBy::App, &Rules::default());
+
+        assert_eq!(summary.total, TimeDelta::minutes(35));
+        assert_eq!(summary.passive, TimeDelta::minutes(15));
+        assert!(summary.groups.iter().all(|group| group.name != "mpv"));
+    }
+
+    #[test]
+    fn total_percent() {
+        let summary = summarize(&intervals(), GroupBy::App, &Rules::default());
+        let percent = summary.groups[1].percent(summary.total);
+
+        assert!((percent - 100.0 / 7.0).abs() < 1e-9);
+        assert_eq!(summary.groups[0].percent(TimeDelta::zero()), 0.0);
+    }
+
+    #[test]
+    fn format_duration_test() {
+        assert_eq!(format_duration(TimeDelta::zero()), "0m 00s");
+        assert_eq!(format_duration(TimeDelta::seconds(59)), "0m 59s");
+        assert_eq!(format_duration(TimeDelta::seconds(3 * 60 + 5)), "3m 05s");
+        assert_eq!(
+            format_duration(TimeDelta::seconds(26 * 3600 + 60 + 1)),
+            "26h 01m 01s"
+        );
+        assert_eq!(format_duration(TimeDelta::seconds(-5)), "0m 00s");
+    }
+}
@@ -0,0 +1,38 @@
+use crate::events::Focused;
+use crate::session::{Interval, IntervalState};
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Minute of the test hour.
+pub fn at(min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 1, 10, min, 0).unwrap()
+}
+
+/// Interval between the minutes, with the window id and title focused.
+pub fn window_interval(
+    start: u32,
+    end: u32,
+    state: IntervalState,
+    window: Option<(&str, &str)>,
+) -> Interval {
+    Interval {
+        start: at(start),
+        end: at(end),
+        state,
+        focused: window.map(|(id, title)| Focused {
+            title: title.into(),
+            id: id.into(),
+            pid: 1,
+            ..Default::default()
+        }),
+    }
+}
+
+/// Interval between the minutes, with the app focused.
+pub fn interval(
+    start: u32,
+    end: u32,
+    state: IntervalState,
+    app: Option<&str>,
+) -> Interval {
+    window_interval(start, end, state, app.map(|id| (id, "title")))
+}
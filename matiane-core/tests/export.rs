@@ -0,0 +1,102 @@
+use anyhow::Result;
+use chrono::{TimeZone, Utc};
+use matiane_core::category::Rules;
+use matiane_core::events::TimedEvent;
+use matiane_core::export::{
+    ExportFormat, ExportOptions, MERGE_GAP, MIN_BLOCK, category_blocks, export,
+    org, records,
+};
+use matiane_core::session::{Interval, sessionize};
+use std::time::Duration;
+
+const EVENTS: &str = include_str!("data/export/events.jsonl");
+const CSV: &str = include_str!("data/export/expected.csv");
+const JSONL: &str = include_str!("data/export/expected.jsonl");
+const ICS: &str = include_str!("data/export/expected.ics");
+const ACTIVITYWATCH: &str =
+    include_str!("data/export/expected.activitywatch.json");
+const TIMEWARRIOR: &str = include_str!("data/export/expected.timew.json");
+const ORG: &str = include_str!("data/export/expected.org");
+
+const RULES: &str = r#"
+    [[rules]]
+    category = "Development"
+    app-id = "emacs"
+
+    [[rules]]
+    category = "Development"
+    app-id = "foot"
+
+    [[rules]]
+    category = "Communication"
+    app-id = "thunderbird"
+"#;
+
+fn intervals() -> Result<Vec<Interval>> {
+    let events = EVENTS
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<TimedEvent>, _>>()?;
+    let end = Utc.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap();
+
+    Ok(sessionize(&events, Duration::from_secs(60), end))
+}
+
+fn rules() -> Result<Rules> {
+    Ok(Rules::new(&toml::from_str(RULES)?)?)
+}
+
+fn exported(format: ExportFormat) -> Result<String> {
+    let options = ExportOptions {
+        hostname: "laptop".into(),
+        ..Default::default()
+    };
+
+    Ok(export(&intervals()?, &rules()?, format, &options)?)
+}
+
+#[test]
+fn export_csv() -> Result<()> {
+    assert_eq!(exported(ExportFormat::Csv)?, CSV);
+
+    Ok(())
+}
+
+#[test]
+fn export_jsonl() -> Result<()> {
+    assert_eq!(exported(ExportFormat::Jsonl)?, JSONL);
+
+    Ok(())
+}
+
+#[test]
+fn export_ics() -> Result<()> {
+    assert_eq!(exported(ExportFormat::Ics)?, ICS);
+
+    Ok(())
+}
+
+#[test]
+fn export_activitywatch() -> Result<()> {
+    assert_eq!(exported(ExportFormat::ActivityWatch)?, ACTIVITYWATCH);
+
+    Ok(())
+}
+
+#[test]
+fn export_timewarrior() -> Result<()> {
+    assert_eq!(exported(ExportFormat::Timewarrior)?, TIMEWARRIOR);
+
+    Ok(())
+}
+
+#[test]
+fn export_org() -> Result<()> {
+    let records = records(&intervals()?, &rules()?);
+    let blocks = category_blocks(&records, MERGE_GAP, MIN_BLOCK);
+
+    // The clocks are in the local time, UTC keeps the test stable.
+    assert_eq!(org::render(&blocks, &Utc), ORG);
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use matiane_core::import::activitywatch::Export;
+use matiane_core::import::{ImportSummary, import};
+use matiane_core::session::{IntervalState, read_intervals};
+use std::fs;
+use std::time::Duration;
+use tempfile::{Builder, TempDir};
+
+const ACTIVITYWATCH: &str = include_str!("data/import/activitywatch.json");
+
+const LIVE_INTERVAL: Duration = Duration::from_secs(60);
+
+const EXISTING: &str = concat!(
+    r#"{"timestamp":"2025-03-10T09:00:00Z","event":{"type":"focused","data":{"title":"main.rs","id":"emacs","pid":42}}}"#,
+    "\n",
+    r#"{"timestamp":"2025-03-10T09:01:00Z","event":{"type":"alive"}}"#,
+    "\n",
+    r#"{"timestamp":"2025-03-10T09:02:00Z","event":{"type":"alive"}}"#,
+    "\n",
+    r#"{"timestamp":"2025-03-10T09:03:00Z","event":{"type":"alive"}}"#,
+    "\n",
+);
+
+fn tmpdir(name: &str) -> TempDir {
+    Builder::new()
+        .prefix(&format!("matiane-core-{}", name))
+        .rand_bytes(10)
+        .tempdir()
+        .unwrap()
+}
+
+fn at(hour: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 10, hour, min, 0).unwrap()
+}
+
+#[tokio::test]
+async fn import_activitywatch() -> Result<()> {
+    let dir = tmpdir("import-activitywatch");
+    let log = dir.path().join("20250310.log");
+    fs::write(&log, EXISTING)?;
+
+    let intervals = Export::parse(ACTIVITYWATCH)?.intervals(None)?;
+    let summary = import(dir.path(), &intervals, LIVE_INTERVAL).await?;
+
+    assert_eq!(summary.dates, vec![at(0, 0).date_naive()]);
+    // The log already covers 09:00 to 09:04.
+    assert_eq!(summary.overlap, TimeDelta::minutes(4));
+
+    let written = fs::read_to_string(&log)?;
+    assert!(EXISTING.lines().all(|line| written.contains(line)));
+    assert_eq!(fs::read_dir(dir.path())?.count(), 1);
+
+    let intervals =
+        read_intervals(dir.path().into(), LIVE_INTERVAL, at(8, 0), at(10, 0))
+            .await?;
+
+    let spans: Vec<_> = intervals
+        .iter()
+        .map(|interval| {
+            (
+                interval.start,
+                interval.end,
+                interval.state,
+                interval.focused.as_ref().map(|focused| focused.id.as_str()),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        spans,
+        vec![
+            (at(8, 50), at(9, 0), IntervalState::Active, Some("firefox")),
+            (at(9, 0), at(9, 4), IntervalState::Active, Some("emacs")),
+            (at(9, 4), at(9, 10), IntervalState::Active, Some("firefox")),
+            (at(9, 10), at(9, 15), IntervalState::Idle, None),
+            (at(9, 15), at(9, 20), IntervalState::Active, Some("foot")),
+            (at(9, 21), at(10, 0), IntervalState::Offline, None),
+        ]
+    );
+
+    // Everything is in the log now, importing again adds nothing.
+    let intervals = Export::parse(ACTIVITYWATCH)?.intervals(None)?;
+    let again = import(dir.path(), &intervals, LIVE_INTERVAL).await?;
+
+    assert_eq!(
+        again,
+        ImportSummary {
+            events: 0,
+            dates: vec![],
+            overlap: TimeDelta::minutes(30),
+        }
+    );
+    assert_eq!(fs::read_to_string(&log)?, written);
+
+    Ok(())
+}
@@ -28,7 +28,7 @@ fn sleep_options(
         name: String::from("sleep"),
         args: vec![seconds.to_string()],
 
-        restart_delay: delay.unwrap_or_else(|| Default::default()),
+        restart_delay: delay.unwrap_or_default(),
     }
 }
 
@@ -1,7 +1,8 @@
 use anyhow::Result;
 use chrono::{TimeZone, Utc};
+use futures::StreamExt;
 use matiane_core::events::{Event, Focused, TimedEvent};
-use matiane_core::store::EventWriter;
+use matiane_core::store::{EventReader, EventWriter, StoreError, read_bounds};
 use std::fs;
 use tempfile::{Builder, TempDir};
 
@@ -61,7 +62,7 @@ async fn store_write_event() -> Result<()> {
 
 #[tokio::test]
 async fn store_write_several_events() -> Result<()> {
-    let now = Utc.with_ymd_and_hms(2025, 01, 01, 0, 0, 0).unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
     let dir = tmpdir("store-write-several-events");
     let pathbuf = dir.path().to_path_buf();
 
@@ -75,7 +76,7 @@ async fn store_write_several_events() -> Result<()> {
     let tests: Vec<TestCase> = vec![
         TestCase {
             event: TimedEvent {
-                timestamp: Utc.with_ymd_and_hms(2025, 01, 01, 0, 0, 1).unwrap(),
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 1).unwrap(),
                 event: Event::Alive,
             },
             expected: r#"{
@@ -85,7 +86,7 @@ async fn store_write_several_events() -> Result<()> {
         },
         TestCase {
             event: TimedEvent {
-                timestamp: Utc.with_ymd_and_hms(2025, 01, 01, 0, 0, 2).unwrap(),
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 2).unwrap(),
                 event: Event::Sleep,
             },
             expected: r#"{
@@ -95,7 +96,7 @@ async fn store_write_several_events() -> Result<()> {
         },
         TestCase {
             event: TimedEvent {
-                timestamp: Utc.with_ymd_and_hms(2025, 01, 01, 0, 0, 3).unwrap(),
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 3).unwrap(),
                 event: Event::Awake,
             },
             expected: r#"{
@@ -105,7 +106,7 @@ async fn store_write_several_events() -> Result<()> {
         },
         TestCase {
             event: TimedEvent {
-                timestamp: Utc.with_ymd_and_hms(2025, 01, 01, 0, 0, 4).unwrap(),
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 4).unwrap(),
                 event: Event::Idle,
             },
             expected: r#"{
@@ -115,7 +116,7 @@ async fn store_write_several_events() -> Result<()> {
         },
         TestCase {
             event: TimedEvent {
-                timestamp: Utc.with_ymd_and_hms(2025, 01, 01, 0, 0, 5).unwrap(),
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 5).unwrap(),
                 event: Event::Active,
             },
             expected: r#"{
@@ -125,11 +126,12 @@ async fn store_write_several_events() -> Result<()> {
         },
         TestCase {
             event: TimedEvent {
-                timestamp: Utc.with_ymd_and_hms(2025, 01, 01, 0, 0, 5).unwrap(),
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 5).unwrap(),
                 event: Event::Focused(Box::new(Focused {
                     title: "This-is-title".to_string(),
                     id: "Program".to_string(),
                     pid: 111,
+                    ..Default::default()
                 })),
             },
             expected: r#"
@@ -146,6 +148,37 @@ async fn store_write_several_events() -> Result<()> {
             }
             "#,
         },
+        TestCase {
+            event: TimedEvent {
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 6).unwrap(),
+                event: Event::Focused(Box::new(Focused {
+                    title: "This-is-title".to_string(),
+                    id: "Program".to_string(),
+                    pid: 111,
+                    workspace: Some("2:mail".to_string()),
+                    workspace_num: Some(2),
+                    output: Some("eDP-1".to_string()),
+                    executable: Some("/usr/bin/program".to_string()),
+                })),
+            },
+            expected: r#"
+            {
+                "timestamp": "2025-01-01T00:00:06Z",
+                "event": {
+                    "type": "focused",
+                    "data": {
+                        "title": "This-is-title",
+                        "id": "Program",
+                        "pid": 111,
+                        "workspace": "2:mail",
+                        "workspace_num": 2,
+                        "output": "eDP-1",
+                        "executable": "/usr/bin/program"
+                    }
+                }
+            }
+            "#,
+        },
     ];
 
     for test in &tests {
@@ -171,7 +204,7 @@ async fn store_write_several_events() -> Result<()> {
 
 #[tokio::test]
 async fn store_rotate_on_write() -> Result<()> {
-    let now = Utc.with_ymd_and_hms(2025, 01, 01, 0, 0, 0).unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
     let dir = tmpdir("store-write-rotate");
     let pathbuf = dir.path().to_path_buf();
 
@@ -180,7 +213,7 @@ async fn store_rotate_on_write() -> Result<()> {
     // five events first day,
     // five events the next.
     for i in 1..=5 {
-        let now = Utc.with_ymd_and_hms(2025, 01, 01, 0, 0, i).unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, i).unwrap();
         let event = TimedEvent {
             timestamp: now,
             event: Event::Alive,
@@ -190,7 +223,7 @@ async fn store_rotate_on_write() -> Result<()> {
     }
 
     for i in 1..=5 {
-        let now = Utc.with_ymd_and_hms(2025, 01, 02, 0, 0, i).unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, i).unwrap();
         let event = TimedEvent {
             timestamp: now,
             event: Event::Alive,
@@ -212,3 +245,243 @@ async fn store_rotate_on_write() -> Result<()> {
 
     Ok(())
 }
+
+async fn write_events(
+    dir: &TempDir,
+    events: &[TimedEvent],
+) -> Result<EventWriter> {
+    let pathbuf = dir.path().to_path_buf();
+    let mut store = EventWriter::open(pathbuf, events[0].timestamp).await?;
+
+    for event in events {
+        store.write(event).await?;
+    }
+
+    store.flush().await?;
+
+    Ok(store)
+}
+
+async fn read_all(
+    reader: &mut EventReader,
+) -> Vec<Result<TimedEvent, StoreError>> {
+    let mut out = vec![];
+
+    loop {
+        match reader.next().await {
+            Ok(Some(event)) => out.push(Ok(event)),
+            Ok(None) => break,
+            Err(err) => out.push(Err(err)),
+        }
+    }
+
+    out
+}
+
+fn alive_at(day: u32, hour: u32) -> TimedEvent {
+    TimedEvent {
+        timestamp: Utc.with_ymd_and_hms(2025, 1, day, hour, 0, 0).unwrap(),
+        event: Event::Alive,
+    }
+}
+
+#[tokio::test]
+async fn store_read_empty_dir() -> Result<()> {
+    let dir = tmpdir("store-read-empty");
+    let from = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+
+    let mut reader =
+        EventReader::open(dir.path().join("missing"), from, to).await?;
+    assert!(reader.next().await?.is_none());
+
+    let mut reader = EventReader::open(dir.path().into(), from, to).await?;
+    assert!(reader.next().await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn store_read_across_days() -> Result<()> {
+    let dir = tmpdir("store-read-across-days");
+
+    let events: Vec<_> = (1..=4)
+        .flat_map(|day| {
+            [alive_at(day, 1), alive_at(day, 12), alive_at(day, 23)]
+        })
+        .collect();
+
+    write_events(&dir, &events).await?;
+    assert_eq!(fs::read_dir(dir.path())?.count(), 4);
+
+    // Whole range, in order.
+    let from = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap();
+    let mut reader = EventReader::open(dir.path().into(), from, to).await?;
+    let read: Vec<_> = read_all(&mut reader)
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(read.len(), events.len());
+    for (read, written) in read.iter().zip(&events) {
+        assert_eq!(read.timestamp, written.timestamp);
+    }
+
+    // Partial range, end exclusive.
+    let from = Utc.with_ymd_and_hms(2025, 1, 2, 12, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2025, 1, 3, 23, 0, 0).unwrap();
+    let mut reader = EventReader::open(dir.path().into(), from, to).await?;
+    let read: Vec<_> = read_all(&mut reader)
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+
+    let timestamps: Vec<_> = read.iter().map(|e| e.timestamp).collect();
+    assert_eq!(
+        timestamps,
+        vec![
+            alive_at(2, 12).timestamp,
+            alive_at(2, 23).timestamp,
+            alive_at(3, 1).timestamp,
+            alive_at(3, 12).timestamp,
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn store_read_malformed_lines() -> Result<()> {
+    let dir = tmpdir("store-read-malformed");
+
+    let contents = [
+        r#"{"timestamp":"2025-01-01T00:00:01Z","event":{"type":"alive"}}"#,
+        r#"{"timestamp":"2025-01-01T00:00:02Z","event":{"type":"#,
+        "",
+        r#"{"timestamp":"2025-01-01T00:00:03Z","event":{"type":"nope"}}"#,
+        r#"{"timestamp":"2025-01-01T00:00:04Z","event":{"type":"idle"}}"#,
+    ]
+    .join("\n");
+
+    fs::write(dir.path().join("20250101.log"), contents)?;
+
+    let from = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+    let mut reader = EventReader::open(dir.path().into(), from, to).await?;
+    let read = read_all(&mut reader).await;
+
+    assert_eq!(read.len(), 4);
+    assert!(matches!(
+        &read[0],
+        Ok(TimedEvent {
+            event: Event::Alive,
+            ..
+        })
+    ));
+    assert!(matches!(
+        &read[1],
+        Err(StoreError::MalformedLine { line: 2, .. })
+    ));
+    assert!(matches!(
+        &read[2],
+        Err(StoreError::MalformedLine { line: 4, .. })
+    ));
+    assert!(matches!(
+        &read[3],
+        Ok(TimedEvent {
+            event: Event::Idle,
+            ..
+        })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn store_read_bounds() -> Result<()> {
+    let dir = tmpdir("store-read-bounds");
+    let date = alive_at(1, 0).timestamp.date_naive();
+
+    assert!(read_bounds(dir.path(), date).await?.is_none());
+
+    // Malformed lines at both ends, the last one longer than a read chunk.
+    let mut contents = vec!["".to_owned(), "{".to_owned()];
+    for hour in (1..=22).cycle().take(200) {
+        contents.push(serde_json::to_string(&alive_at(1, hour))?);
+    }
+    contents.push(serde_json::to_string(&alive_at(1, 23))?);
+    contents.extend([r#"{"timestamp":"#.repeat(500), "".to_owned()]);
+
+    fs::write(dir.path().join("20250101.log"), contents.join("\n"))?;
+
+    assert_eq!(
+        read_bounds(dir.path(), date).await?,
+        Some((alive_at(1, 1).timestamp, alive_at(1, 23).timestamp))
+    );
+
+    fs::write(dir.path().join("20250101.log"), "\n{\n")?;
+    assert!(read_bounds(dir.path(), date).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn store_read_focused_without_workspace() -> Result<()> {
+    let dir = tmpdir("store-read-legacy-focused");
+
+    let contents = [
+        r#"{"timestamp":"2025-01-01T00:00:01Z","event":{"type":"focused","#,
+        r#""data":{"title":"vim","id":"zsh","pid":5}}}"#,
+    ]
+    .concat();
+
+    fs::write(dir.path().join("20250101.log"), contents)?;
+
+    let from = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+    let mut reader = EventReader::open(dir.path().into(), from, to).await?;
+    let read = read_all(&mut reader).await;
+
+    assert_eq!(read.len(), 1);
+    let Ok(TimedEvent {
+        event: Event::Focused(focused),
+        ..
+    }) = &read[0]
+    else {
+        panic!("Must be a focus event.");
+    };
+
+    assert_eq!(
+        **focused,
+        Focused {
+            title: "vim".into(),
+            id: "zsh".into(),
+            pid: 5,
+            workspace: None,
+            workspace_num: None,
+            output: None,
+            executable: None,
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn store_read_stream() -> Result<()> {
+    let dir = tmpdir("store-read-stream");
+    let events = [alive_at(1, 1), alive_at(2, 1), alive_at(3, 1)];
+
+    write_events(&dir, &events).await?;
+
+    let from = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap();
+    let reader = EventReader::open(dir.path().into(), from, to).await?;
+    let read: Vec<_> = reader.into_stream().collect().await;
+
+    assert_eq!(read.len(), 2);
+    assert!(read.iter().all(Result::is_ok));
+
+    Ok(())
+}
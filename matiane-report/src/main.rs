@@ -0,0 +1,291 @@
+use anyhow::{Context, Result, bail};
+use chrono::{Local, NaiveDate, TimeDelta};
+use clap::{
+    ArgGroup, arg, builder::PossibleValuesParser, command, value_parser,
+};
+use log::{LevelFilter, debug};
+use matiane_core::category::{CategoriesConfig, Rules};
+use matiane_core::config::GeneralConfig;
+use matiane_core::export::{
+    ExportConfig, ExportFormat, ExportOptions, activitywatch,
+};
+use matiane_core::log::init_global_logger;
+use matiane_core::session::{local_day, passive_as_active, read_intervals};
+use matiane_core::summary::GroupBy;
+use matiane_core::xdg::Xdg;
+use serde::Deserialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+mod report;
+
+use report::{Format, Report};
+
+#[derive(PartialEq, Debug, Deserialize, Default, Clone)]
+#[serde(rename_all = "kebab-case")]
+struct ReportConfig {
+    #[serde(default)]
+    general: GeneralConfig,
+    #[serde(default)]
+    categories: CategoriesConfig,
+    #[serde(default)]
+    export: ExportConfig,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let xdg = Xdg::new(matiane_core::NAME.into());
+
+    let ParsedArgs {
+        config_file,
+        log_level,
+        from,
+        to,
+        group_by,
+        format,
+        export,
+        hostname,
+        merge_gap,
+        min_block,
+    } = parse_args(&xdg, Local::now().date_naive())?;
+
+    init_global_logger(log_level)?;
+
+    let cfg = load_config(&config_file).await?;
+    let rules = Rules::new(&cfg.categories)
+        .context("Failed to load the category rules")?;
+    debug!(
+        "Reporting {} - {} from {:?}",
+        from, to, cfg.general.state_dir
+    );
+
+    let mut days = vec![];
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        let (start, end) = local_day(date);
+        let mut intervals = read_intervals(
+            cfg.general.state_dir.clone(),
+            cfg.general.live_interval(),
+            start,
+            end,
+        )
+        .await
+        .with_context(|| format!("Failed to read the logs for {}", date))?;
+
+        if cfg.general.passive_as_active {
+            passive_as_active(&mut intervals);
+        }
+
+        days.push((date, intervals));
+    }
+
+    let export_options = ExportOptions {
+        hostname: hostname.unwrap_or_else(activitywatch::hostname),
+        merge_gap: TimeDelta::minutes(
+            merge_gap.unwrap_or(cfg.export.merge_gap).into(),
+        ),
+        min_block: TimeDelta::minutes(
+            min_block.unwrap_or(cfg.export.min_block).into(),
+        ),
+    };
+
+    let mut rendered = match export {
+        Some(export) => {
+            let intervals: Vec<_> = days
+                .into_iter()
+                .flat_map(|(_, intervals)| intervals)
+                .collect();
+            matiane_core::export::export(
+                &intervals,
+                &rules,
+                export,
+                &export_options,
+            )?
+        }
+        None => {
+            let Some(report) = Report::new(group_by, &rules, days) else {
+                return Ok(());
+            };
+
+            report.render(format)?
+        }
+    };
+
+    if !rendered.ends_with('\n') {
+        rendered.push('\n');
+    }
+
+    // Piping into `head` and alike should not end with an error.
+    match std::io::stdout().lock().write_all(rendered.as_bytes()) {
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => result.context("Failed to write the report"),
+    }
+}
+
+struct ParsedArgs {
+    config_file: PathBuf,
+    log_level: LevelFilter,
+    from: NaiveDate,
+    to: NaiveDate,
+    group_by: GroupBy,
+    format: Format,
+    export: Option<ExportFormat>,
+    hostname: Option<String>,
+    /// Minutes, overrides the config.
+    merge_gap: Option<u32>,
+    /// Minutes, overrides the config.
+    min_block: Option<u32>,
+}
+
+fn parse_args(xdg: &Xdg, today: NaiveDate) -> Result<ParsedArgs> {
+    let possible_levels: Vec<_> =
+        LevelFilter::iter().map(|v| v.as_str()).collect();
+
+    let matches = command!("Sway matiane report")
+        .arg(
+            arg!(-c --config <FILE> "Sets a custom config file")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            arg!(-l --level <LEVEL> "Sets a log level")
+                .value_parser(PossibleValuesParser::new(possible_levels))
+                .ignore_case(true)
+                .default_value("WARN"),
+        )
+        .arg(
+            arg!(--from <DATE> "First day of the report (YYYY-MM-DD)")
+                .value_parser(parse_date),
+        )
+        .arg(
+            arg!(--to <DATE> "Last day of the report (YYYY-MM-DD)")
+                .value_parser(parse_date),
+        )
+        .arg(arg!(--today "Reports today (default)"))
+        .arg(arg!(--week "Reports the current week, starting on Monday"))
+        .group(
+            ArgGroup::new("range")
+                .args(["today", "week"])
+                .conflicts_with_all(["from", "to"]),
+        )
+        .arg(
+            arg!(-g --"group-by" <GROUP> "Groups the totals")
+                .value_parser(["app", "title", "category", "workspace"])
+                .default_value("app"),
+        )
+        .arg(
+            arg!(-f --format <FORMAT> "Sets the output format")
+                .value_parser(["table", "json", "csv"])
+                .default_value("table"),
+        )
+        .arg(
+            arg!(-e --export <FORMAT> "Exports the focus intervals instead")
+                .value_parser([
+                    "csv",
+                    "jsonl",
+                    "ics",
+                    "activitywatch",
+                    "timew",
+                    "org",
+                ])
+                .conflicts_with_all(["group-by", "format"]),
+        )
+        .arg(
+            arg!(--hostname <HOST> "Host of the ActivityWatch buckets")
+                .requires("export"),
+        )
+        .arg(
+            arg!(--"merge-gap" <MINUTES> "Longest pause within a block")
+                .value_parser(value_parser!(u32))
+                .requires("export"),
+        )
+        .arg(
+            arg!(--"min-block" <MINUTES> "Shortest block to export")
+                .value_parser(value_parser!(u32))
+                .requires("export"),
+        )
+        .get_matches();
+
+    let log_level =
+        LevelFilter::from_str(matches.get_one::<String>("level").unwrap())?;
+    let config_file = matches
+        .get_one::<PathBuf>("config")
+        .cloned()
+        .unwrap_or_else(|| xdg.config_dir().join("config.toml"));
+
+    let (from, to) = if matches.get_flag("week") {
+        let monday = today.week(chrono::Weekday::Mon).first_day();
+        (monday, today)
+    } else if matches.get_flag("today") {
+        (today, today)
+    } else {
+        let to = matches.get_one::<NaiveDate>("to").copied().unwrap_or(today);
+        let from = matches.get_one::<NaiveDate>("from").copied().unwrap_or(to);
+        (from, to)
+    };
+
+    if from > to {
+        bail!("--from ({}) must not be after --to ({}).", from, to);
+    }
+
+    let group_by = match matches.get_one::<String>("group-by").unwrap().as_str()
+    {
+        "title" => GroupBy::Title,
+        "category" => GroupBy::Category,
+        "workspace" => GroupBy::Workspace,
+        _ => GroupBy::App,
+    };
+
+    let format = match matches.get_one::<String>("format").unwrap().as_str() {
+        "json" => Format::Json,
+        "csv" => Format::Csv,
+        _ => Format::Table,
+    };
+
+    let export = matches
+        .get_one::<String>("export")
+        .map(|export| match export.as_str() {
+            "jsonl" => ExportFormat::Jsonl,
+            "ics" => ExportFormat::Ics,
+            "activitywatch" => ExportFormat::ActivityWatch,
+            "timew" => ExportFormat::Timewarrior,
+            "org" => ExportFormat::Org,
+            _ => ExportFormat::Csv,
+        });
+
+    Ok(ParsedArgs {
+        config_file,
+        log_level,
+        from,
+        to,
+        group_by,
+        format,
+        export,
+        hostname: matches.get_one::<String>("hostname").cloned(),
+        merge_gap: matches.get_one::<u32>("merge-gap").copied(),
+        min_block: matches.get_one::<u32>("min-block").copied(),
+    })
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|err| format!("{} (expected YYYY-MM-DD)", err))
+}
+
+async fn load_config(file: &PathBuf) -> Result<ReportConfig> {
+    let file_str = match tokio::fs::read_to_string(file).await {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(ReportConfig::default());
+        }
+        Err(e) => return Err(e).context("Failed to read configuration file"),
+    };
+
+    let mut parsed = toml::from_str::<ReportConfig>(&file_str)
+        .context("Failed to parse TOML from configuration file")?;
+    parsed
+        .general
+        .apply_deprecated(&file_str)
+        .context("Failed to parse TOML from configuration file")?;
+
+    Ok(parsed)
+}
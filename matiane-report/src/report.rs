@@ -0,0 +1,422 @@
+use chrono::{NaiveDate, TimeDelta};
+use matiane_core::category::Rules;
+use matiane_core::export::csv::field as csv_field;
+use matiane_core::session::Interval;
+use matiane_core::summary::{
+    GroupBy, Summary, Total, format_duration, summarize,
+};
+use serde::Serialize;
+use std::fmt::Write;
+
+const MAX_NAME_WIDTH: usize = 60;
+const DURATION_WIDTH: usize = 13;
+const PERCENT_WIDTH: usize = 7;
+const INDENT: &str = "  ";
+const PASSIVE: &str = "Passive presence";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayReport {
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub summary: Summary,
+}
+
+/// Active time per day for the inclusive date range, plus the total of the
+/// whole range.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: GroupBy,
+    pub days: Vec<DayReport>,
+    pub total: Summary,
+}
+
+impl Report {
+    pub fn new(
+        group_by: GroupBy,
+        rules: &Rules,
+        days: Vec<(NaiveDate, Vec<Interval>)>,
+    ) -> Option<Self> {
+        let from = days.first()?.0;
+        let to = days.last()?.0;
+
+        let all: Vec<Interval> = days
+            .iter()
+            .flat_map(|(_, intervals)| intervals.iter().cloned())
+            .collect();
+
+        let days = days
+            .into_iter()
+            .map(|(date, intervals)| DayReport {
+                date,
+                summary: summarize(&intervals, group_by, rules),
+            })
+            .collect();
+
+        Some(Report {
+            from,
+            to,
+            group_by,
+            days,
+            total: summarize(&all, group_by, rules),
+        })
+    }
+
+    pub fn render(&self, format: Format) -> Result<String, serde_json::Error> {
+        match format {
+            Format::Table => Ok(self.table()),
+            Format::Json => serde_json::to_string_pretty(self),
+            Format::Csv => Ok(self.csv()),
+        }
+    }
+
+    fn table(&self) -> String {
+        let mut sections: Vec<(String, &Summary)> = self
+            .days
+            .iter()
+            .map(|day| {
+                (day.date.format("%a %Y-%m-%d").to_string(), &day.summary)
+            })
+            .collect();
+
+        if self.days.len() > 1 {
+            sections.push((
+                format!("Total {} - {}", self.from, self.to),
+                &self.total,
+            ));
+        }
+
+        let width = sections
+            .iter()
+            .flat_map(|(title, summary)| {
+                let groups = summary.groups.iter().flat_map(|group| {
+                    std::iter::once(INDENT.len() + name_width(group)).chain(
+                        group
+                            .children
+                            .iter()
+                            .map(|child| INDENT.len() * 2 + name_width(child)),
+                    )
+                });
+
+                let passive = (summary.passive > TimeDelta::zero())
+                    .then(|| INDENT.len() + PASSIVE.len());
+
+                std::iter::once(title.chars().count())
+                    .chain(passive)
+                    .chain(groups)
+            })
+            .max()
+            .unwrap_or(0)
+            .min(MAX_NAME_WIDTH);
+
+        let mut out = String::new();
+
+        for (index, (title, summary)) in sections.iter().enumerate() {
+            if index > 0 {
+                out.push('\n');
+            }
+
+            table_row(&mut out, width, title, summary.total, None);
+
+            if summary.passive > TimeDelta::zero() {
+                table_row(
+                    &mut out,
+                    width,
+                    &format!("{}{}", INDENT, PASSIVE),
+                    summary.passive,
+                    None,
+                );
+            }
+
+            for group in &summary.groups {
+                table_row(
+                    &mut out,
+                    width,
+                    &format!("{}{}", INDENT, group.name),
+                    group.duration,
+                    Some(group.percent(summary.total)),
+                );
+
+                for child in &group.children {
+                    table_row(
+                        &mut out,
+                        width,
+                        &format!("{}{}", INDENT.repeat(2), child.name),
+                        child.duration,
+                        Some(child.percent(summary.total)),
+                    );
+                }
+            }
+        }
+
+        out
+    }
+
+    fn csv(&self) -> String {
+        let mut out = match self.group_by {
+            GroupBy::App => String::from("date,app,title,seconds\n"),
+            GroupBy::Title => String::from("date,title,seconds\n"),
+            GroupBy::Category => String::from("date,category,app,seconds\n"),
+            GroupBy::Workspace => String::from("date,workspace,app,seconds\n"),
+        };
+
+        for day in &self.days {
+            for group in &day.summary.groups {
+                if self.group_by == GroupBy::Title {
+                    let _ = writeln!(
+                        out,
+                        "{},{},{}",
+                        day.date,
+                        csv_field(&group.name),
+                        group.duration.num_seconds()
+                    );
+                    continue;
+                }
+
+                for child in &group.children {
+                    let _ = writeln!(
+                        out,
+                        "{},{},{},{}",
+                        day.date,
+                        csv_field(&group.name),
+                        csv_field(&child.name),
+                        child.duration.num_seconds()
+                    );
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn name_width(total: &Total) -> usize {
+    total.name.chars().count()
+}
+
+fn table_row(
+    out: &mut String,
+    width: usize,
+    name: &str,
+    duration: TimeDelta,
+    percent: Option<f64>,
+) {
+    let percent = percent
+        .map(|percent| format!("{:.1}%", percent))
+        .unwrap_or_default();
+
+    let _ = writeln!(
+        out,
+        "{:<width$} {:>dwidth$} {:>pwidth$}",
+        truncate(name, width),
+        format_duration(duration),
+        percent,
+        width = width,
+        dwidth = DURATION_WIDTH,
+        pwidth = PERCENT_WIDTH,
+    );
+}
+
+fn truncate(name: &str, width: usize) -> String {
+    if name.chars().count() <= width {
+        return name.into();
+    }
+
+    let mut truncated: String =
+        name.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use chrono::{DateTime, TimeZone, Utc};
+    use matiane_core::events::Focused;
+    use matiane_core::session::IntervalState;
+
+    fn at(day: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, day, 10, min, 0).unwrap()
+    }
+
+    fn active(
+        day: u32,
+        start: u32,
+        end: u32,
+        id: &str,
+        title: &str,
+    ) -> Interval {
+        Interval {
+            start: at(day, start),
+            end: at(day, end),
+            state: IntervalState::Active,
+            focused: Some(Focused {
+                title: title.into(),
+                id: id.into(),
+                pid: 1,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, day).unwrap()
+    }
+
+    fn report(group_by: GroupBy) -> Report {
+        Report::new(
+            group_by,
+            &Rules::default(),
+            vec![
+                (
+                    date(1),
+                    vec![
+                        active(1, 0, 30, "firefox", "Mail, inbox"),
+                        active(1, 30, 40, "zsh", "vim"),
+                    ],
+                ),
+                (date(2), vec![active(2, 0, 20, "zsh", "vim")]),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn report_empty() {
+        assert!(Report::new(GroupBy::App, &Rules::default(), vec![]).is_none());
+    }
+
+    #[test]
+    fn report_table() {
+        let table = report(GroupBy::App).render(Format::Table).unwrap();
+        let expected = [
+            "Wed 2025-01-01                40m 00s        ",
+            "  firefox                     30m 00s   75.0%",
+            "    Mail, inbox               30m 00s   75.0%",
+            "  zsh                         10m 00s   25.0%",
+            "    vim                       10m 00s   25.0%",
+            "",
+            "Thu 2025-01-02                20m 00s        ",
+            "  zsh                         20m 00s  100.0%",
+            "    vim                       20m 00s  100.0%",
+            "",
+            "Total 2025-01-01 - 2025-01-02 1h 00m 00s        ",
+            "  firefox                     30m 00s   50.0%",
+            "    Mail, inbox               30m 00s   50.0%",
+            "  zsh                         30m 00s   50.0%",
+            "    vim                       30m 00s   50.0%",
+        ];
+
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), expected.len());
+        for (line, expected) in lines.iter().zip(expected) {
+            assert_eq!(line.split_whitespace().collect::<Vec<_>>(), {
+                expected.split_whitespace().collect::<Vec<_>>()
+            });
+        }
+
+        let widths: Vec<usize> = lines
+            .iter()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().count())
+            .collect();
+        assert!(widths.windows(2).all(|w| w[0] == w[1]));
+    }
+
+    #[test]
+    fn report_single_day_has_no_total() {
+        let report = Report::new(
+            GroupBy::Title,
+            &Rules::default(),
+            vec![(date(1), vec![active(1, 0, 30, "zsh", "vim")])],
+        )
+        .unwrap();
+        let table = report.render(Format::Table).unwrap();
+
+        assert!(!table.contains("Total"));
+        assert_eq!(table.lines().count(), 2);
+    }
+
+    #[test]
+    fn report_passive() -> Result<()> {
+        let passive = Interval {
+            state: IntervalState::Passive,
+            ..active(1, 30, 50, "mpv", "film")
+        };
+        let report = Report::new(
+            GroupBy::App,
+            &Rules::default(),
+            vec![(date(1), vec![active(1, 0, 30, "zsh", "vim"), passive])],
+        )
+        .unwrap();
+
+        let table = report.render(Format::Table)?;
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines[1].split_whitespace().collect::<Vec<_>>(),
+            vec!["Passive", "presence", "20m", "00s"]
+        );
+        assert!(!table.contains("mpv"));
+
+        let value: serde_json::Value =
+            serde_json::from_str(&report.render(Format::Json)?)?;
+        assert_eq!(value["days"][0]["total"], 1800);
+        assert_eq!(value["days"][0]["passive"], 1200);
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_csv() {
+        let csv = report(GroupBy::App).render(Format::Csv).unwrap();
+
+        assert_eq!(
+            csv,
+            "date,app,title,seconds\n\
+             2025-01-01,firefox,\"Mail, inbox\",1800\n\
+             2025-01-01,zsh,vim,600\n\
+             2025-01-02,zsh,vim,1200\n"
+        );
+
+        let csv = report(GroupBy::Title).render(Format::Csv).unwrap();
+
+        assert_eq!(
+            csv,
+            "date,title,seconds\n\
+             2025-01-01,\"Mail, inbox\",1800\n\
+             2025-01-01,vim,600\n\
+             2025-01-02,vim,1200\n"
+        );
+    }
+
+    #[test]
+    fn report_json() -> Result<()> {
+        let json = report(GroupBy::App).render(Format::Json)?;
+        let value: serde_json::Value = serde_json::from_str(&json)?;
+
+        assert_eq!(value["from"], "2025-01-01");
+        assert_eq!(value["to"], "2025-01-02");
+        assert_eq!(value["group_by"], "app");
+        assert_eq!(value["days"][0]["date"], "2025-01-01");
+        assert_eq!(value["days"][0]["total"], 2400);
+        assert_eq!(value["days"][0]["groups"][0]["name"], "firefox");
+        assert_eq!(value["total"]["total"], 3600);
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncate_names() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("much longer name", 6), "much …");
+    }
+}
@@ -1,36 +1,62 @@
 use iced::alignment;
 use iced::widget::{
-    Theme, button, column, container, pick_list, row, rule, space, text,
-    tooltip,
+    Column, Theme, button, canvas, column, container, pick_list, row, rule,
+    scrollable, space, text, tooltip,
 };
+use log::error;
+use matiane_core::category::Rules;
+use matiane_core::session::{
+    Interval, local_day, passive_as_active, read_intervals,
+};
+use matiane_core::summary::{GroupBy, Total, format_duration, summarize};
 use std::collections::BTreeSet;
 
-use chrono::TimeZone;
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use iced::{Element, Fill, Subscription, Task};
 
 use crate::config;
 use crate::datefile;
 use crate::icon;
+use crate::screen::Screen;
+use crate::timeline::Timeline;
 
 const DEFAULT_LIGHT: Theme = Theme::Light;
 const DEFAULT_DARK: Theme = Theme::Nord;
 
+const SIDEBAR_WIDTH: f32 = 220.0;
+
 #[derive(Default, Debug)]
-pub enum Screen {
+pub enum State {
     #[default]
-    Initial,
+    Loading,
+    Initialized,
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct Day {
+    timeline: Timeline,
+    intervals: Vec<Interval>,
 }
 
 #[derive(Default, Debug)]
-pub enum State {
+pub enum DayState {
     #[default]
+    Empty,
     Loading,
-    Initialized,
+    Loaded(Box<Day>),
+    Failed(String),
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     LoadedDates(BTreeSet<datefile::DateFile>),
+    LoadFailed(String),
+    SelectDate(NaiveDate),
+    LoadedDay(NaiveDate, Result<Vec<Interval>, String>),
+    GroupBy(GroupBy),
+    ToggleGroup(String),
+    Reload,
     ThemeToggle,
 }
 
@@ -41,13 +67,22 @@ pub struct App {
     state: State,
 
     config: config::MatianeConfig,
+    rules: Rules,
     tz_offset: chrono::FixedOffset,
     loaded_dates: Option<BTreeSet<datefile::DateFile>>,
+    selected_date: Option<NaiveDate>,
+    day: DayState,
+    group_by: GroupBy,
+    expanded: BTreeSet<String>,
 }
 
 impl App {
-    pub fn new(cfg: config::MatianeConfig) -> (Self, Task<Message>) {
+    pub fn new(
+        cfg: config::MatianeConfig,
+        rules: Rules,
+    ) -> (Self, Task<Message>) {
         let tz_offset = *chrono::Local::now().offset();
+        let load = load_dates(&cfg);
 
         (
             App {
@@ -56,10 +91,15 @@ impl App {
                 state: State::default(),
 
                 config: cfg,
+                rules,
                 tz_offset,
                 loaded_dates: None,
+                selected_date: None,
+                day: DayState::default(),
+                group_by: GroupBy::default(),
+                expanded: BTreeSet::new(),
             },
-            Task::none(),
+            load,
         )
     }
 
@@ -70,7 +110,60 @@ impl App {
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::LoadedDates(dates) => {
+                self.selected_date =
+                    self.selected_date.or(dates.last().map(|d| d.date));
                 self.loaded_dates = Some(dates);
+                self.state = State::Initialized;
+
+                if let Some(date) = self.selected_date {
+                    return self.load_day(date);
+                }
+            }
+            Message::LoadFailed(err) => {
+                error!("Failed to load log files: {}", err);
+                self.state = State::Failed(err);
+            }
+            Message::SelectDate(date) => {
+                self.selected_date = Some(date);
+                return self.load_day(date);
+            }
+            Message::LoadedDay(date, result) => {
+                if self.selected_date != Some(date) {
+                    return Task::none();
+                }
+
+                self.day = match result {
+                    Ok(mut intervals) => {
+                        if self.config.general.passive_as_active {
+                            passive_as_active(&mut intervals);
+                        }
+
+                        let (start, end) = local_day(date);
+                        let timeline =
+                            Timeline::new(start, end, intervals.clone());
+
+                        DayState::Loaded(Box::new(Day {
+                            timeline,
+                            intervals,
+                        }))
+                    }
+                    Err(err) => {
+                        error!("Failed to load {}: {}", date, err);
+                        DayState::Failed(err)
+                    }
+                };
+            }
+            Message::GroupBy(group_by) => {
+                self.group_by = group_by;
+                self.expanded.clear();
+            }
+            Message::ToggleGroup(name) => {
+                if !self.expanded.remove(&name) {
+                    self.expanded.insert(name);
+                }
+            }
+            Message::Reload => {
+                return load_dates(&self.config);
             }
             Message::ThemeToggle => {
                 if self.theme == DEFAULT_LIGHT {
@@ -78,15 +171,25 @@ impl App {
                 } else {
                     self.theme = DEFAULT_LIGHT
                 }
+
+                if let DayState::Loaded(day) = &self.day {
+                    day.timeline.clear();
+                }
             }
         }
         Task::none()
     }
 
     pub fn view(&self) -> Element<'_, Message> {
-        let content = match self.state {
-            State::Loading => loading(),
-            State::Initialized => nothing_space(),
+        let content = match (&self.screen, &self.state) {
+            (_, State::Loading) => loading(),
+            (_, State::Failed(err)) => failed(err),
+            (Screen::Initial, State::Initialized) => row![
+                self.view_sidebar(),
+                rule::vertical(2).style(rule::weak),
+                self.view_day(),
+            ]
+            .into(),
         };
 
         let out: Element<'_, Message> = column![
@@ -126,8 +229,24 @@ impl App {
             tooltip::Position::Bottom,
         );
 
+        let reload = tooltip(
+            button(icon::clock().height(Fill).center())
+                .padding(10)
+                .on_press(Message::Reload),
+            container("Reload logs")
+                .padding(10)
+                .style(container::rounded_box),
+            tooltip::Position::Bottom,
+        );
+
+        let theme_icon = if self.theme == DEFAULT_DARK {
+            icon::sun()
+        } else {
+            icon::moon()
+        };
+
         let theme_switch = tooltip(
-            button(icon::moon().height(Fill).center())
+            button(theme_icon.height(Fill).center())
                 .padding(10)
                 .on_press(Message::ThemeToggle),
             container(if self.theme == DEFAULT_DARK {
@@ -144,6 +263,7 @@ impl App {
             logo_name,
             container(row![
                 container(timezone).padding(10),
+                container(reload).padding(10),
                 container(theme_switch).padding(10),
             ])
             .width(Fill)
@@ -159,6 +279,171 @@ impl App {
         header.into()
     }
 
+    pub fn view_sidebar(&self) -> Element<'_, Message> {
+        let Some(dates) = &self.loaded_dates else {
+            return nothing_space();
+        };
+
+        if dates.is_empty() {
+            return container("No recorded days.")
+                .padding(20)
+                .width(SIDEBAR_WIDTH)
+                .into();
+        }
+
+        let items = dates.iter().rev().map(|file| {
+            let date = file.date;
+            let start = file.start_date.with_timezone(&Local);
+            let end = file.end_date.with_timezone(&Local);
+
+            let style = if self.selected_date == Some(date) {
+                button::primary
+            } else {
+                button::text
+            };
+
+            button(column![
+                text(date.format("%a, %d %b %Y").to_string()),
+                text(format!(
+                    "{} - {}",
+                    start.format("%H:%M"),
+                    end.format("%H:%M")
+                ))
+                .size(12),
+            ])
+            .style(style)
+            .width(Fill)
+            .padding([5, 10])
+            .on_press(Message::SelectDate(date))
+            .into()
+        });
+
+        scrollable(Column::with_children(items).spacing(2).padding(10))
+            .width(SIDEBAR_WIDTH)
+            .height(Fill)
+            .into()
+    }
+
+    pub fn view_day(&self) -> Element<'_, Message> {
+        let file = self.selected_date.and_then(|date| {
+            self.loaded_dates
+                .as_ref()?
+                .iter()
+                .find(|file| file.date == date)
+        });
+
+        let Some(file) = file else {
+            return container("Select a day.")
+                .width(Fill)
+                .height(Fill)
+                .align_x(iced::Alignment::Center)
+                .align_y(iced::Alignment::Center)
+                .into();
+        };
+
+        let format_time = |time: DateTime<Utc>| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        };
+
+        let content: Element<'_, Message> = match &self.day {
+            DayState::Empty => nothing_space(),
+            DayState::Loading => loading(),
+            DayState::Failed(err) => failed(err),
+            DayState::Loaded(day) if day.timeline.is_empty() => {
+                text("Nothing was recorded on this day.").into()
+            }
+            DayState::Loaded(day) => scrollable(
+                column![
+                    canvas(&day.timeline)
+                        .width(Fill)
+                        .height(day.timeline.height()),
+                    self.view_totals(&day.intervals),
+                ]
+                .spacing(20),
+            )
+            .into(),
+        };
+
+        column![
+            text(file.date.format("%A, %d %B %Y").to_string()).size(20),
+            text(format!(
+                "Recorded from {} to {}",
+                format_time(file.start_date),
+                format_time(file.end_date)
+            ))
+            .size(12),
+            content,
+        ]
+        .spacing(10)
+        .padding(20)
+        .width(Fill)
+        .into()
+    }
+
+    pub fn view_totals(&self, intervals: &[Interval]) -> Element<'_, Message> {
+        let summary = summarize(intervals, self.group_by, &self.rules);
+
+        let mut active =
+            format!("Active time: {}", format_duration(summary.total));
+        if summary.passive > chrono::TimeDelta::zero() {
+            active.push_str(&format!(
+                ", passive presence: {}",
+                format_duration(summary.passive)
+            ));
+        }
+
+        let header = row![
+            text(active).size(16).width(Fill),
+            pick_list(GroupBy::ALL, Some(self.group_by), Message::GroupBy),
+        ]
+        .align_y(iced::Alignment::Center);
+
+        let mut rows = Column::new().spacing(2);
+
+        for group in &summary.groups {
+            let expanded = self.expanded.contains(&group.name);
+            let on_press = (!group.children.is_empty())
+                .then(|| Message::ToggleGroup(group.name.clone()));
+            let marker = match (on_press.is_some(), expanded) {
+                (false, _) => "  ",
+                (true, false) => "▸ ",
+                (true, true) => "▾ ",
+            };
+
+            rows = rows.push(total_row(marker, group, summary.total, on_press));
+
+            if expanded {
+                for child in &group.children {
+                    rows = rows.push(total_row(
+                        "      ",
+                        child,
+                        summary.total,
+                        None,
+                    ));
+                }
+            }
+        }
+
+        column![header, rows].spacing(10).padding([0, 20]).into()
+    }
+
+    fn load_day(&mut self, date: NaiveDate) -> Task<Message> {
+        let (start, end) = local_day(date);
+        let state_dir = self.config.general.state_dir.clone();
+        let live_interval = self.config.general.live_interval();
+
+        self.day = DayState::Loading;
+
+        Task::perform(
+            read_intervals(state_dir, live_interval, start, end),
+            move |result| {
+                Message::LoadedDay(date, result.map_err(|e| e.to_string()))
+            },
+        )
+    }
+
     pub fn theme(&self) -> Theme {
         self.theme.clone()
     }
@@ -177,6 +462,45 @@ pub fn loading<'a, Message: 'a>() -> Element<'a, Message> {
         .into()
 }
 
+pub fn failed<'a, Message: 'a>(err: &str) -> Element<'a, Message> {
+    container(text(format!("Failed to load: {}", err)))
+        .width(Fill)
+        .height(Fill)
+        .align_x(iced::Alignment::Center)
+        .align_y(iced::Alignment::Center)
+        .into()
+}
+
+fn total_row<'a>(
+    marker: &str,
+    total: &Total,
+    all: chrono::TimeDelta,
+    on_press: Option<Message>,
+) -> Element<'a, Message> {
+    button(
+        row![
+            text(format!("{}{}", marker, total.name)).width(Fill),
+            text(format_duration(total.duration)).width(110),
+            text(format!("{:.1}%", total.percent(all))).width(60),
+        ]
+        .spacing(10),
+    )
+    .style(button::text)
+    .width(Fill)
+    .padding([2, 10])
+    .on_press_maybe(on_press)
+    .into()
+}
+
 pub fn nothing_space<'a, Message: 'a>() -> Element<'a, Message> {
     space().into()
 }
+
+fn load_dates(cfg: &config::MatianeConfig) -> Task<Message> {
+    let state_dir = cfg.general.state_dir.clone();
+
+    Task::perform(datefile::list_files(state_dir), |result| match result {
+        Ok(dates) => Message::LoadedDates(dates),
+        Err(err) => Message::LoadFailed(err.to_string()),
+    })
+}
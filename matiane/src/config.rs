@@ -1,3 +1,4 @@
+use matiane_core::category::CategoriesConfig;
 use matiane_core::config::GeneralConfig;
 use serde::Deserialize;
 
@@ -12,4 +13,6 @@ pub struct MatianeConfig {
     pub general: GeneralConfig,
     #[serde(default)]
     pub gui: GuiConfig,
+    #[serde(default)]
+    pub categories: CategoriesConfig,
 }
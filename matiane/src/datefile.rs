@@ -1,34 +1,64 @@
-use chrono::{DateTime, Utc};
-use futures::StreamExt;
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
 use log::debug;
-use std::collections::BTreeSet;
+use matiane_core::session::local_day;
+use matiane_core::store::{StoreError, list_dates, read_bounds};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 use thiserror::Error;
-use tokio::fs::read_dir;
-use tokio_stream::wrappers::ReadDirStream;
 
 #[derive(Debug, Error)]
 pub enum DateFileError {
-    #[error("Store IO Error: {0}")]
-    Io(#[from] std::io::Error),
+    #[error("Store Error: {0}")]
+    Store(#[from] StoreError),
 }
 
-#[derive(Debug, Clone)]
+/// Local day with recorded events, with the bounds of the logs covering it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DateFile {
-    pub file_date: DateTime<Utc>,
+    pub date: NaiveDate,
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
 }
 
-// pub async fn list_files(path: impl AsRef<Path>) -> Result<Vec<DateFile>, DateFileError> {
-//     let dirstream = ReadDirStream::new(read_dir(path).await?);
+/// Local days of the log files. The files are kept by UTC date, so a day may
+/// span two of them; only the first and last event of each file are read.
+pub async fn list_files(
+    path: impl AsRef<Path>,
+) -> Result<BTreeSet<DateFile>, DateFileError> {
+    let path = path.as_ref();
+    let mut days: BTreeMap<NaiveDate, (DateTime<Utc>, DateTime<Utc>)> =
+        BTreeMap::new();
 
-//     let list = dirstream
-//         .filter(async |r| r.is_ok())
-//         .collect::<Vec<std::fs::DirEntry>>()
-//         .await;
+    for date in list_dates(path).await? {
+        let Some((first, last)) = read_bounds(path, date).await? else {
+            debug!("No events in the log file for {}.", date);
+            continue;
+        };
 
-//     println!("List: {:?}", list);
+        let mut day = first.with_timezone(&Local).date_naive();
+        let last_day = last.with_timezone(&Local).date_naive();
 
-//     Ok(list)
-// }
+        while day <= last_day {
+            let (start, end) = local_day(day);
+            let (start, end) = (first.max(start), last.min(end));
+
+            days.entry(day)
+                .and_modify(|(first, last)| {
+                    *first = (*first).min(start);
+                    *last = (*last).max(end);
+                })
+                .or_insert((start, end));
+
+            day += TimeDelta::days(1);
+        }
+    }
+
+    Ok(days
+        .into_iter()
+        .map(|(date, (start_date, end_date))| DateFile {
+            date,
+            start_date,
+            end_date,
+        })
+        .collect())
+}
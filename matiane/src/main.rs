@@ -9,6 +9,7 @@ use clap::{
     command, value_parser,
 };
 
+use matiane_core::category::Rules;
 use matiane_core::log::init_global_logger;
 use matiane_core::xdg::Xdg;
 
@@ -17,6 +18,7 @@ mod config;
 mod datefile;
 mod icon;
 mod screen;
+mod timeline;
 
 use app::App;
 
@@ -31,12 +33,15 @@ fn main() -> anyhow::Result<()> {
     init_global_logger(log_level)?;
 
     let config = load_config(&config_file)?;
+    let rules = Rules::new(&config.categories)
+        .context("Failed to load the category rules")?;
 
-    let app_init = move || App::new(config.clone());
+    let app_init = move || App::new(config.clone(), rules.clone());
 
     iced::application(app_init, App::update, App::view)
         .title(App::title)
         .theme(App::theme)
+        .subscription(App::subscription)
         .font(icon::FONT)
         .run()?;
 
@@ -89,7 +94,11 @@ fn load_config(file: &PathBuf) -> anyhow::Result<config::MatianeConfig> {
         Err(e) => return Err(e).context("Failed to read configuration file"),
     };
 
-    let parsed = toml::from_str::<config::MatianeConfig>(&file_str)
+    let mut parsed = toml::from_str::<config::MatianeConfig>(&file_str)
+        .context("Failed to parse TOML from configuration file")?;
+    parsed
+        .general
+        .apply_deprecated(&file_str)
         .context("Failed to parse TOML from configuration file")?;
 
     Ok(parsed)
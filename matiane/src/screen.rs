@@ -1 +1,5 @@
-pub enum Screen {}
+#[derive(Default, Debug)]
+pub enum Screen {
+    #[default]
+    Initial,
+}
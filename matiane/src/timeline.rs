@@ -0,0 +1,360 @@
+use chrono::{DateTime, Local, Utc};
+use iced::mouse;
+use iced::widget::Action;
+use iced::widget::canvas::{self, Event, Frame, Geometry, Path, Text};
+use iced::{Color, Point, Rectangle, Renderer, Size, Theme};
+use matiane_core::session::{Interval, IntervalState};
+use matiane_core::summary::format_duration;
+
+const LABEL_WIDTH: f32 = 140.0;
+const AXIS_HEIGHT: f32 = 24.0;
+const LANE_HEIGHT: f32 = 28.0;
+const LANE_GAP: f32 = 4.0;
+const TEXT_SIZE: f32 = 12.0;
+const TOOLTIP_PADDING: f32 = 6.0;
+
+const COLORS: [Color; 8] = [
+    Color::from_rgb8(0x5e, 0x81, 0xac),
+    Color::from_rgb8(0xa3, 0xbe, 0x8c),
+    Color::from_rgb8(0xd0, 0x87, 0x70),
+    Color::from_rgb8(0xb4, 0x8e, 0xad),
+    Color::from_rgb8(0xeb, 0xcb, 0x8b),
+    Color::from_rgb8(0x88, 0xc0, 0xd0),
+    Color::from_rgb8(0xbf, 0x61, 0x6a),
+    Color::from_rgb8(0x8f, 0xbc, 0xbb),
+];
+
+#[derive(Debug)]
+struct Lane {
+    id: String,
+    blocks: Vec<Interval>,
+}
+
+/// Single day, one lane per application id with the idle, asleep and offline
+/// ranges greyed out across all lanes.
+#[derive(Debug)]
+pub struct Timeline {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    lanes: Vec<Lane>,
+    inactive: Vec<Interval>,
+    cache: canvas::Cache,
+}
+
+impl Timeline {
+    pub fn new(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        intervals: Vec<Interval>,
+    ) -> Self {
+        let mut lanes: Vec<Lane> = vec![];
+        let mut inactive = vec![];
+
+        for interval in intervals {
+            let Some(focused) = &interval.focused else {
+                inactive.push(interval);
+                continue;
+            };
+
+            match lanes.iter_mut().find(|lane| lane.id == focused.id) {
+                Some(lane) => lane.blocks.push(interval),
+                None => lanes.push(Lane {
+                    id: focused.id.clone(),
+                    blocks: vec![interval],
+                }),
+            }
+        }
+
+        Timeline {
+            start,
+            end,
+            lanes,
+            inactive,
+            cache: canvas::Cache::default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lanes.is_empty() && self.inactive.is_empty()
+    }
+
+    pub fn height(&self) -> f32 {
+        AXIS_HEIGHT + self.lanes.len() as f32 * (LANE_HEIGHT + LANE_GAP)
+    }
+
+    /// Drops the cached drawing, e.g. after a theme change.
+    pub fn clear(&self) {
+        self.cache.clear();
+    }
+
+    fn time_to_x(&self, time: DateTime<Utc>, width: f32) -> f32 {
+        let total = (self.end - self.start).num_seconds().max(1) as f32;
+        let offset = (time - self.start).num_seconds() as f32;
+        let lanes_width = (width - LABEL_WIDTH).max(0.0);
+
+        LABEL_WIDTH + lanes_width * (offset / total).clamp(0.0, 1.0)
+    }
+
+    fn lane_y(index: usize) -> f32 {
+        AXIS_HEIGHT + index as f32 * (LANE_HEIGHT + LANE_GAP)
+    }
+
+    fn block_bounds(
+        &self,
+        interval: &Interval,
+        y: f32,
+        width: f32,
+    ) -> Rectangle {
+        let x = self.time_to_x(interval.start, width);
+        let end = self.time_to_x(interval.end, width);
+
+        Rectangle {
+            x,
+            y,
+            width: (end - x).max(1.0),
+            height: LANE_HEIGHT,
+        }
+    }
+
+    fn format_time(&self, time: DateTime<Utc>) -> String {
+        time.with_timezone(&Local).format("%H:%M:%S").to_string()
+    }
+
+    fn describe(&self, interval: &Interval) -> Vec<String> {
+        let range = format!(
+            "{} - {} ({})",
+            self.format_time(interval.start),
+            self.format_time(interval.end),
+            format_duration(interval.duration()),
+        );
+
+        match &interval.focused {
+            Some(focused) => {
+                let app = match &focused.workspace {
+                    Some(workspace) => {
+                        format!("{} on {}", focused.id, workspace)
+                    }
+                    None => focused.id.clone(),
+                };
+
+                let mut lines = vec![focused.title.clone(), app, range];
+                if interval.state == IntervalState::Passive {
+                    lines.push(state_name(interval.state).into());
+                }
+
+                lines
+            }
+            None => vec![state_name(interval.state).into(), range],
+        }
+    }
+
+    /// Interval under the cursor, blocks first and then the inactive ranges.
+    fn hovered(&self, position: Point, width: f32) -> Option<&Interval> {
+        for (index, lane) in self.lanes.iter().enumerate() {
+            let y = Self::lane_y(index);
+
+            for block in &lane.blocks {
+                if self.block_bounds(block, y, width).contains(position) {
+                    return Some(block);
+                }
+            }
+        }
+
+        self.inactive.iter().find(|interval| {
+            let x = self.time_to_x(interval.start, width);
+            let end = self.time_to_x(interval.end, width);
+
+            position.x >= x && position.x <= end && position.y >= AXIS_HEIGHT
+        })
+    }
+
+    fn draw_static(&self, frame: &mut Frame, theme: &Theme) {
+        let palette = theme.extended_palette();
+        let width = frame.width();
+        let height = frame.height();
+
+        for interval in &self.inactive {
+            let x = self.time_to_x(interval.start, width);
+            let end = self.time_to_x(interval.end, width);
+            let color = match interval.state {
+                IntervalState::Offline => palette.danger.weak.color,
+                _ => palette.background.strong.color,
+            };
+
+            frame.fill_rectangle(
+                Point::new(x, AXIS_HEIGHT),
+                Size::new((end - x).max(1.0), height - AXIS_HEIGHT),
+                Color { a: 0.4, ..color },
+            );
+        }
+
+        let hours = (self.end - self.start).num_hours();
+        for hour in 0..=hours {
+            let time = self.start + chrono::TimeDelta::hours(hour);
+            let x = self.time_to_x(time, width);
+
+            frame.fill_rectangle(
+                Point::new(x, AXIS_HEIGHT - 4.0),
+                Size::new(1.0, height - AXIS_HEIGHT + 4.0),
+                palette.background.weak.color,
+            );
+
+            if hour % 2 == 0 && hour < hours {
+                frame.fill_text(Text {
+                    content: time
+                        .with_timezone(&Local)
+                        .format("%H:%M")
+                        .to_string(),
+                    position: Point::new(x + 2.0, 2.0),
+                    color: palette.background.base.text,
+                    size: TEXT_SIZE.into(),
+                    ..Text::default()
+                });
+            }
+        }
+
+        for (index, lane) in self.lanes.iter().enumerate() {
+            let y = Self::lane_y(index);
+            let color = COLORS[index % COLORS.len()];
+
+            frame.fill_text(Text {
+                content: lane.id.clone(),
+                position: Point::new(
+                    4.0,
+                    y + LANE_HEIGHT / 2.0 - TEXT_SIZE / 2.0,
+                ),
+                max_width: LABEL_WIDTH - 8.0,
+                color: palette.background.base.text,
+                size: TEXT_SIZE.into(),
+                ..Text::default()
+            });
+
+            for block in &lane.blocks {
+                let bounds = self.block_bounds(block, y, width);
+                let color = match block.state {
+                    IntervalState::Passive => Color { a: 0.4, ..color },
+                    _ => color,
+                };
+
+                frame.fill_rectangle(bounds.position(), bounds.size(), color);
+            }
+        }
+    }
+
+    fn draw_tooltip(
+        &self,
+        frame: &mut Frame,
+        theme: &Theme,
+        position: Point,
+        lines: Vec<String>,
+    ) {
+        let palette = theme.extended_palette();
+        let line_height = TEXT_SIZE * 1.3;
+        let longest =
+            lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+
+        let size = Size::new(
+            longest as f32 * TEXT_SIZE * 0.6 + TOOLTIP_PADDING * 2.0,
+            lines.len() as f32 * line_height + TOOLTIP_PADDING * 2.0,
+        );
+
+        // Keep the tooltip inside the canvas.
+        let x = if position.x + 12.0 + size.width > frame.width() {
+            (position.x - 12.0 - size.width).max(0.0)
+        } else {
+            position.x + 12.0
+        };
+        let y =
+            (position.y + 12.0).min((frame.height() - size.height).max(0.0));
+
+        let background =
+            Path::rounded_rectangle(Point::new(x, y), size, 4.0.into());
+        frame.fill(&background, palette.background.weakest.color);
+
+        for (index, line) in lines.into_iter().enumerate() {
+            frame.fill_text(Text {
+                content: line,
+                position: Point::new(
+                    x + TOOLTIP_PADDING,
+                    y + TOOLTIP_PADDING + index as f32 * line_height,
+                ),
+                color: palette.background.weakest.text,
+                size: TEXT_SIZE.into(),
+                ..Text::default()
+            });
+        }
+    }
+}
+
+impl<Message> canvas::Program<Message> for Timeline {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: &Event,
+        _bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Option<Action<Message>> {
+        match event {
+            // Tooltip follows the cursor.
+            Event::Mouse(
+                mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft,
+            ) => Some(Action::request_redraw()),
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let timeline = self.cache.draw(renderer, bounds.size(), |frame| {
+            self.draw_static(frame, theme);
+        });
+
+        let Some(position) = cursor.position_in(bounds) else {
+            return vec![timeline];
+        };
+
+        let Some(hovered) = self.hovered(position, bounds.width) else {
+            return vec![timeline];
+        };
+
+        let mut frame = Frame::new(renderer, bounds.size());
+        self.draw_tooltip(&mut frame, theme, position, self.describe(hovered));
+
+        vec![timeline, frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match cursor.position_in(bounds) {
+            Some(position)
+                if self.hovered(position, bounds.width).is_some() =>
+            {
+                mouse::Interaction::Pointer
+            }
+            _ => mouse::Interaction::default(),
+        }
+    }
+}
+
+fn state_name(state: IntervalState) -> &'static str {
+    match state {
+        IntervalState::Active => "Active",
+        IntervalState::Idle => "Idle",
+        IntervalState::Passive => "Passive presence",
+        IntervalState::Asleep => "Asleep",
+        IntervalState::Paused => "Paused",
+        IntervalState::Offline => "Offline",
+    }
+}
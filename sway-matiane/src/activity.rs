@@ -0,0 +1,289 @@
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
+use log::warn;
+use matiane_core::events::{Event, Focused, TimedEvent};
+use matiane_core::session::{
+    Interval, IntervalState, LOOKBACK, Sessionizer, local_day,
+};
+use matiane_core::store::{EventReader, StoreError};
+use matiane_core::summary::format_duration;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// What the daemon is tracking right now.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub state: IntervalState,
+    pub focused: Option<Focused>,
+    /// When the focused window got the focus.
+    pub since: Option<DateTime<Utc>>,
+    /// Active time of the local day so far, in seconds.
+    #[serde(with = "seconds")]
+    pub today: TimeDelta,
+}
+
+impl Snapshot {
+    /// Human readable lines, durations are counted up to `now`.
+    pub fn describe(&self, paused: bool, now: DateTime<Utc>) -> Vec<String> {
+        let mut lines = vec![];
+
+        if paused {
+            lines.push("Tracking is paused.".to_owned());
+        }
+
+        if let Some(focused) = &self.focused {
+            lines.push(focused.title.clone());
+        }
+
+        if let Some(since) = self.since
+            && !paused
+        {
+            lines.push(format!("Focused for {}", format_duration(now - since)));
+        }
+
+        lines.push(format!("Today: {}", format_duration(self.today)));
+        lines
+    }
+}
+
+/// Today's activity, kept up to date with the events the daemon writes.
+#[derive(Debug)]
+pub struct Activity {
+    sessionizer: Sessionizer,
+    passive_as_active: bool,
+    day: NaiveDate,
+    day_start: DateTime<Utc>,
+    /// Active time of the intervals closed during the day.
+    closed: TimeDelta,
+    focused: Option<Focused>,
+    since: Option<DateTime<Utc>>,
+}
+
+impl Activity {
+    pub fn new(
+        live_interval: Duration,
+        passive_as_active: bool,
+        at: DateTime<Utc>,
+    ) -> Self {
+        let day = at.with_timezone(&Local).date_naive();
+
+        Activity {
+            sessionizer: Sessionizer::new(live_interval),
+            passive_as_active,
+            day,
+            day_start: local_day(day).0,
+            closed: TimeDelta::zero(),
+            focused: None,
+            since: None,
+        }
+    }
+
+    /// Catches up with what has already been logged today.
+    pub async fn load(
+        dir: PathBuf,
+        live_interval: Duration,
+        passive_as_active: bool,
+        at: DateTime<Utc>,
+    ) -> Result<Self, StoreError> {
+        let mut activity = Activity::new(live_interval, passive_as_active, at);
+        let mut reader =
+            EventReader::open(dir, activity.day_start - LOOKBACK, at).await?;
+
+        loop {
+            match reader.next().await {
+                Ok(Some(event)) => activity.push(&event),
+                Ok(None) => break,
+                Err(err @ StoreError::MalformedLine { .. }) => {
+                    warn!("Skipping: {}", err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(activity)
+    }
+
+    pub fn push(&mut self, event: &TimedEvent) {
+        let at = event.timestamp;
+        self.roll(at);
+
+        for interval in self.sessionizer.push(event) {
+            self.closed += self.counted(&interval);
+        }
+
+        match &event.event {
+            Event::Focused(focused)
+                if self.focused.as_ref() != Some(focused.as_ref()) =>
+            {
+                self.focused = Some(focused.as_ref().clone());
+                self.since = Some(at);
+            }
+            Event::Disconnected | Event::Private => {
+                self.focused = None;
+                self.since = None;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn snapshot(&mut self, at: DateTime<Utc>) -> Snapshot {
+        self.roll(at);
+
+        let open = self
+            .sessionizer
+            .current(at)
+            .map(|interval| self.counted(&interval))
+            .unwrap_or_default();
+
+        Snapshot {
+            state: self.sessionizer.state(),
+            focused: self.focused.clone(),
+            since: self.since,
+            today: self.closed + open,
+        }
+    }
+
+    /// Starts counting anew after the local midnight.
+    fn roll(&mut self, at: DateTime<Utc>) {
+        let day = at.with_timezone(&Local).date_naive();
+        if day == self.day {
+            return;
+        }
+
+        self.day = day;
+        self.day_start = local_day(day).0;
+        self.closed = TimeDelta::zero();
+    }
+
+    /// Active part of the interval within the day.
+    fn counted(&self, interval: &Interval) -> TimeDelta {
+        let active = match interval.state {
+            IntervalState::Active => true,
+            IntervalState::Passive => self.passive_as_active,
+            _ => false,
+        };
+
+        interval
+            .clip(self.day_start, DateTime::<Utc>::MAX_UTC)
+            .filter(|_| active)
+            .map(|interval| interval.duration())
+            .unwrap_or_default()
+    }
+}
+
+mod seconds {
+    use chrono::TimeDelta;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(duration: &TimeDelta, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_i64(duration.num_seconds())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<TimeDelta, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        i64::deserialize(deserializer).map(TimeDelta::seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const LIVE: Duration = Duration::from_secs(60);
+
+    fn at(hour: u32, min: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(2025, 1, 1, hour, min, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    fn focused(id: &str) -> Event {
+        Event::Focused(Box::new(Focused {
+            title: "title".into(),
+            id: id.into(),
+            pid: 1,
+            ..Default::default()
+        }))
+    }
+
+    fn push(activity: &mut Activity, timestamp: DateTime<Utc>, event: Event) {
+        activity.push(&TimedEvent { timestamp, event });
+    }
+
+    #[test]
+    fn activity_today() {
+        let mut activity = Activity::new(LIVE, false, at(10, 0));
+
+        push(&mut activity, at(10, 0), focused("firefox"));
+        push(&mut activity, at(10, 0), Event::Alive);
+        push(&mut activity, at(10, 1), Event::Alive);
+        push(&mut activity, at(10, 1), Event::Idle);
+        push(&mut activity, at(10, 2), Event::Alive);
+        push(&mut activity, at(10, 2), Event::Active);
+        push(&mut activity, at(10, 2), Event::Alive);
+        push(&mut activity, at(10, 3), focused("firefox"));
+        push(&mut activity, at(10, 3), focused("zsh"));
+
+        let snapshot = activity.snapshot(at(10, 4));
+
+        assert_eq!(
+            snapshot.focused.map(|focused| focused.id),
+            Some("zsh".into())
+        );
+        assert_eq!(snapshot.since, Some(at(10, 3)));
+        assert_eq!(snapshot.today, TimeDelta::minutes(3));
+    }
+
+    #[test]
+    fn activity_passive() {
+        let mut activity = Activity::new(LIVE, false, at(10, 0));
+        push(&mut activity, at(10, 0), focused("mpv"));
+        push(&mut activity, at(10, 1), Event::Passive);
+
+        assert_eq!(activity.snapshot(at(10, 2)).today, TimeDelta::minutes(1));
+
+        let mut activity = Activity::new(LIVE, true, at(10, 0));
+        push(&mut activity, at(10, 0), focused("mpv"));
+        push(&mut activity, at(10, 1), Event::Passive);
+
+        assert_eq!(activity.snapshot(at(10, 2)).today, TimeDelta::minutes(2));
+    }
+
+    #[test]
+    fn activity_private() {
+        let mut activity = Activity::new(LIVE, false, at(10, 0));
+        push(&mut activity, at(10, 0), focused("firefox"));
+        push(&mut activity, at(10, 1), Event::Private);
+
+        let snapshot = activity.snapshot(at(10, 2));
+
+        assert_eq!(snapshot.focused, None);
+        assert_eq!(snapshot.since, None);
+        assert_eq!(snapshot.today, TimeDelta::minutes(1));
+    }
+
+    #[test]
+    fn activity_midnight() {
+        let mut activity = Activity::new(LIVE, false, at(23, 58));
+        push(&mut activity, at(23, 58), focused("firefox"));
+        push(&mut activity, at(23, 59), Event::Alive);
+
+        let midnight = at(23, 59) + TimeDelta::minutes(1);
+        push(&mut activity, midnight, Event::Alive);
+        assert_eq!(activity.snapshot(midnight).today, TimeDelta::zero());
+
+        // Only the part after midnight counts.
+        let minute = TimeDelta::minutes(1);
+        push(&mut activity, midnight + minute, focused("zsh"));
+
+        let snapshot = activity.snapshot(midnight + minute * 2);
+        assert_eq!(snapshot.today, TimeDelta::minutes(2));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::control::Status;
+use chrono::{DateTime, TimeDelta, Utc};
+use matiane_core::category::Rules;
+use matiane_core::session::IntervalState;
+use matiane_core::summary::format_duration;
+use serde::{Deserialize, Serialize};
+
+/// Block name in the i3bar protocol.
+pub const BLOCK_NAME: &str = "matiane";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BarFormat {
+    /// Waybar `custom` module with `return-type = "json"`.
+    #[default]
+    Waybar,
+    /// Endless array of status lines for i3bar and swaybar.
+    I3bar,
+}
+
+/// Waybar custom module output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Waybar {
+    pub text: String,
+    pub tooltip: String,
+    /// State of the daemon: the interval state, `paused` or `offline`.
+    pub class: String,
+    /// Today's share of the daily goal.
+    pub percentage: u8,
+}
+
+impl Waybar {
+    /// Output for the daemon status, `None` when the daemon is not running.
+    pub fn new(
+        status: Option<&Status>,
+        rules: &Rules,
+        goal: TimeDelta,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let Some(status) = status else {
+            return Waybar {
+                text: "Offline".into(),
+                tooltip: "sway-matiane is not running.".into(),
+                class: "offline".into(),
+                percentage: 0,
+            };
+        };
+
+        let activity = &status.activity;
+        let today = format_duration(activity.today);
+
+        let (text, class) = match &activity.focused {
+            _ if status.paused => (format!("Paused {}", today), "paused"),
+            Some(focused) => (
+                format!("{} {}", rules.category(focused), today),
+                state_class(activity.state),
+            ),
+            None => (today, state_class(activity.state)),
+        };
+
+        Waybar {
+            text,
+            tooltip: activity.describe(status.paused, now).join("\n"),
+            class: class.into(),
+            percentage: percentage(activity.today, goal),
+        }
+    }
+}
+
+/// Single block of an i3bar status line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct I3Block {
+    pub name: &'static str,
+    /// Same as the Waybar class, so the bar config can tell them apart.
+    pub instance: String,
+    pub full_text: String,
+    pub urgent: bool,
+}
+
+impl From<Waybar> for I3Block {
+    fn from(waybar: Waybar) -> Self {
+        I3Block {
+            name: BLOCK_NAME,
+            urgent: waybar.class == "paused",
+            instance: waybar.class,
+            full_text: waybar.text,
+        }
+    }
+}
+
+/// First lines of the i3bar protocol, before the status lines.
+pub fn i3bar_header() -> String {
+    "{\"version\":1}\n[".into()
+}
+
+fn state_class(state: IntervalState) -> &'static str {
+    match state {
+        IntervalState::Active => "active",
+        IntervalState::Idle => "idle",
+        IntervalState::Passive => "passive",
+        IntervalState::Asleep => "asleep",
+        IntervalState::Paused => "paused",
+        IntervalState::Offline => "offline",
+    }
+}
+
+fn percentage(today: TimeDelta, goal: TimeDelta) -> u8 {
+    if goal <= TimeDelta::zero() {
+        return 0;
+    }
+
+    let share = today.num_seconds() * 100 / goal.num_seconds();
+    share.clamp(0, 100) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity::Snapshot;
+    use anyhow::Result;
+    use chrono::TimeZone;
+    use matiane_core::events::Focused;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap()
+    }
+
+    fn status() -> Status {
+        Status {
+            paused: false,
+            paused_until: None,
+            connected: true,
+            activity: Snapshot {
+                state: IntervalState::Active,
+                focused: Some(Focused {
+                    title: "main.rs".into(),
+                    id: "emacs".into(),
+                    pid: 1,
+                    ..Default::default()
+                }),
+                since: Some(now() - TimeDelta::minutes(2)),
+                today: TimeDelta::minutes(120),
+            },
+        }
+    }
+
+    fn rules() -> Result<Rules> {
+        let config = toml::from_str(
+            r#"
+            [[rules]]
+            category = "Development"
+            app-id = "emacs"
+            "#,
+        )?;
+
+        Ok(Rules::new(&config)?)
+    }
+
+    #[test]
+    fn bar_waybar() -> Result<()> {
+        let goal = TimeDelta::hours(8);
+        let waybar = Waybar::new(Some(&status()), &rules()?, goal, now());
+
+        assert_eq!(
+            waybar,
+            Waybar {
+                text: "Development 2h 00m 00s".into(),
+                tooltip: "main.rs\nFocused for 2m 00s\nToday: 2h 00m 00s"
+                    .into(),
+                class: "active".into(),
+                percentage: 25,
+            }
+        );
+
+        assert_eq!(
+            serde_json::to_string(&waybar)?,
+            concat!(
+                r#"{"text":"Development 2h 00m 00s","#,
+                r#""tooltip":"main.rs\nFocused for 2m 00s\nToday: 2h 00m 00s","#,
+                r#""class":"active","percentage":25}"#
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn bar_states() -> Result<()> {
+        let rules = rules()?;
+        let goal = TimeDelta::hours(1);
+
+        let mut status = status();
+        status.activity.state = IntervalState::Idle;
+        let idle = Waybar::new(Some(&status), &rules, goal, now());
+        assert_eq!(idle.class, "idle");
+        assert_eq!(idle.percentage, 100);
+
+        status.paused = true;
+        let paused = Waybar::new(Some(&status), &rules, goal, now());
+        assert_eq!(paused.text, "Paused 2h 00m 00s");
+        assert_eq!(paused.class, "paused");
+
+        let block = I3Block::from(paused);
+        assert!(block.urgent);
+        assert_eq!(
+            serde_json::to_string(&block)?,
+            concat!(
+                r#"{"name":"matiane","instance":"paused","#,
+                r#""full_text":"Paused 2h 00m 00s","urgent":true}"#
+            )
+        );
+
+        let offline = Waybar::new(None, &rules, goal, now());
+        assert_eq!(offline.class, "offline");
+        assert_eq!(offline.percentage, 0);
+
+        Ok(())
+    }
+}
@@ -1,43 +1,67 @@
+use crate::bar::BarFormat;
+use crate::privacy::PrivacyRuleConfig;
+use matiane_core::category::CategoriesConfig;
 use matiane_core::config::GeneralConfig;
-use serde::{Deserialize, Deserializer};
-use std::time::Duration;
+use serde::Deserialize;
 
-const LIVE_INTERVAL: Duration = Duration::from_secs(60);
+fn default_idle_timeout() -> u32 {
+    60
+}
 
-fn default_live_interval() -> Duration {
-    LIVE_INTERVAL
+fn default_daily_goal() -> u32 {
+    8 * 60
 }
 
-fn default_idle_timeout() -> u32 {
-    60
+fn default_bar_interval() -> u32 {
+    5
+}
+
+/// Where the idle notifications come from.
+#[derive(PartialEq, Eq, Debug, Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdleBackend {
+    /// Spawns `swayidle` and receives its callbacks as signals.
+    #[default]
+    Swayidle,
+    /// Asks the compositor directly with ext-idle-notify or kde-idle.
+    Wayland,
+    /// Follows the logind session `IdleHint`, the idle timeout is up to
+    /// whoever sets the hint.
+    Logind,
 }
 
-fn deserialize_interval<'de, D>(deserializer: D) -> Result<Duration, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let secs = u64::deserialize(deserializer)?;
-    Ok(Duration::from_secs(secs))
+/// Where the sleep and lock notifications come from.
+#[derive(PartialEq, Eq, Debug, Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum SleepBackend {
+    /// `before-sleep` and `after-resume` hooks of `swayidle`.
+    #[default]
+    Swayidle,
+    /// logind `PrepareForSleep` and the session `Lock`/`Unlock` signals.
+    Logind,
 }
 
 #[derive(PartialEq, Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct SwayMatianeConfig {
-    #[serde(
-        default = "default_live_interval",
-        deserialize_with = "deserialize_interval"
-    )]
-    pub live_interval: Duration,
-
     #[serde(default = "default_idle_timeout")]
     pub idle_timeout: u32,
+    #[serde(default)]
+    pub idle_backend: IdleBackend,
+    #[serde(default)]
+    pub sleep_backend: SleepBackend,
+    /// Checked in order on every focus event before it is written.
+    #[serde(default)]
+    pub privacy: Vec<PrivacyRuleConfig>,
 }
 
 impl Default for SwayMatianeConfig {
     fn default() -> Self {
         Self {
-            live_interval: default_live_interval(),
             idle_timeout: default_idle_timeout(),
+            idle_backend: IdleBackend::default(),
+            sleep_backend: SleepBackend::default(),
+            privacy: vec![],
         }
     }
 }
@@ -51,10 +75,50 @@ pub struct SwayCliConfig {
     pub sway: SwayMatianeConfig,
 }
 
+#[derive(PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BarConfig {
+    #[serde(default)]
+    pub format: BarFormat,
+    /// Minutes of active time that make the full percentage.
+    #[serde(default = "default_daily_goal")]
+    pub daily_goal: u32,
+    /// Seconds between the updates.
+    #[serde(default = "default_bar_interval")]
+    pub interval: u32,
+}
+
+impl Default for BarConfig {
+    fn default() -> Self {
+        Self {
+            format: BarFormat::default(),
+            daily_goal: default_daily_goal(),
+            interval: default_bar_interval(),
+        }
+    }
+}
+
+/// Parts of the config used by `matiane-ctl`.
+#[derive(PartialEq, Debug, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct CtlConfig {
+    #[serde(default)]
+    pub general: GeneralConfig,
+    /// Only the privacy rules are used, on the imported windows.
+    #[serde(default)]
+    pub sway: SwayMatianeConfig,
+    #[serde(default)]
+    pub categories: CategoriesConfig,
+    #[serde(default)]
+    pub bar: BarConfig,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::privacy::PrivacyAction;
     use anyhow::Result;
+    use std::time::Duration;
 
     #[test]
     fn deserialize_config() -> Result<()> {
@@ -75,6 +139,7 @@ mod tests {
                 config: SwayCliConfig {
                     general: GeneralConfig {
                         state_dir: "/root/state".into(),
+                        ..Default::default()
                     },
                     ..Default::default()
                 },
@@ -85,8 +150,21 @@ mod tests {
             },
             SuccessCase {
                 config: SwayCliConfig {
-                    sway: SwayMatianeConfig {
-                        live_interval: Duration::from_secs(150),
+                    general: GeneralConfig {
+                        live_interval: Some(Duration::from_secs(150)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                raw: r#"
+                [general]
+                live-interval = 150
+                "#,
+            },
+            SuccessCase {
+                config: SwayCliConfig {
+                    general: GeneralConfig {
+                        live_interval: Some(Duration::from_secs(150)),
                         ..Default::default()
                     },
                     ..Default::default()
@@ -96,6 +174,22 @@ mod tests {
                 live-interval = 150
                 "#,
             },
+            SuccessCase {
+                config: SwayCliConfig {
+                    general: GeneralConfig {
+                        live_interval: Some(Duration::from_secs(20)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                raw: r#"
+                [general]
+                live-interval = 20
+
+                [sway]
+                live-interval = 150
+                "#,
+            },
             SuccessCase {
                 config: SwayCliConfig {
                     sway: SwayMatianeConfig {
@@ -113,36 +207,126 @@ mod tests {
                 config: SwayCliConfig {
                     general: GeneralConfig {
                         state_dir: "/root/state2".into(),
+                        live_interval: Some(Duration::from_secs(20)),
+                        passive_as_active: true,
                     },
                     sway: SwayMatianeConfig {
-                        live_interval: Duration::from_secs(20),
                         idle_timeout: 21,
+                        idle_backend: IdleBackend::Swayidle,
+                        sleep_backend: SleepBackend::Swayidle,
+                        privacy: vec![],
                     },
                 },
                 raw: r#"
                 [general]
                 state-dir = "/root/state2"
+                live-interval = 20
+                passive-as-active = true
 
                 [sway]
-                live-interval = 20
                 idle-timeout = 21
                 "#,
             },
+            SuccessCase {
+                config: SwayCliConfig {
+                    sway: SwayMatianeConfig {
+                        idle_backend: IdleBackend::Wayland,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                raw: r#"
+                [sway]
+                idle-backend = "wayland"
+                "#,
+            },
+            SuccessCase {
+                config: SwayCliConfig {
+                    sway: SwayMatianeConfig {
+                        idle_backend: IdleBackend::Logind,
+                        sleep_backend: SleepBackend::Logind,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                raw: r#"
+                [sway]
+                idle-backend = "logind"
+                sleep-backend = "logind"
+                "#,
+            },
+            SuccessCase {
+                config: SwayCliConfig {
+                    sway: SwayMatianeConfig {
+                        privacy: vec![PrivacyRuleConfig {
+                            action: PrivacyAction::Redact,
+                            app_id: Some("^firefox$".into()),
+                            title: Some("(?i)private".into()),
+                            placeholder: Some("Private".into()),
+                        }],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                raw: r#"
+                [[sway.privacy]]
+                action = "redact"
+                app-id = "^firefox$"
+                title = "(?i)private"
+                placeholder = "Private"
+                "#,
+            },
         ];
 
         for test in tests {
-            let decoded =
-                toml::from_str::<SwayCliConfig>(test.raw).map_err(|err| {
+            let mut decoded = toml::from_str::<SwayCliConfig>(test.raw)
+                .map_err(|err| {
                     anyhow::anyhow!(
                         "Failed: {:?} test case, err: {:?}",
                         test,
                         err
                     )
                 })?;
+            decoded.general.apply_deprecated(test.raw)?;
 
             assert_eq!(decoded, test.config);
         }
 
         Ok(())
     }
+
+    #[test]
+    fn deserialize_ctl_config() -> Result<()> {
+        let decoded = toml::from_str::<CtlConfig>(
+            r#"
+            [general]
+            live-interval = 20
+
+            [sway]
+            idle-timeout = 21
+
+            [[categories.rules]]
+            category = "Development"
+            app-id = "emacs"
+
+            [bar]
+            format = "i3bar"
+            daily-goal = 360
+            "#,
+        )?;
+
+        assert_eq!(decoded.general.live_interval(), Duration::from_secs(20));
+        assert_eq!(decoded.categories.rules.len(), 1);
+        assert_eq!(
+            decoded.bar,
+            BarConfig {
+                format: BarFormat::I3bar,
+                daily_goal: 360,
+                interval: 5,
+            }
+        );
+        assert_eq!(toml::from_str::<CtlConfig>("")?, CtlConfig::default());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,250 @@
+// JSON lines over a Unix socket: one request per line, answered with one
+// response per line.
+
+use crate::activity::Snapshot;
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, StreamExt};
+use log::{debug, warn};
+use matiane_core::events::Focused;
+use matiane_core::xdg;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::{JoinHandle, spawn};
+use tokio_util::codec::{Framed, LinesCodec, LinesCodecError};
+use tokio_util::sync::CancellationToken;
+
+pub const SOCKET_NAME: &str = "control.sock";
+
+/// Longest request line that is accepted.
+const MAX_LINE: usize = 64 * 1024;
+
+#[derive(Debug, Error)]
+pub enum ControlError {
+    #[error("Control socket IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Control message is not valid: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Control line error: {0}")]
+    Line(#[from] LinesCodecError),
+
+    #[error("Daemon closed the connection without a response.")]
+    Closed,
+}
+
+/// Socket of the running daemon, under the runtime dir.
+pub fn socket_path() -> PathBuf {
+    xdg::runtime_dir(Some(matiane_core::NAME)).join(SOCKET_NAME)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "command")]
+pub enum Request {
+    Status,
+    /// Pause tracking, for a number of minutes or until resumed.
+    Pause {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        minutes: Option<u64>,
+    },
+    Resume,
+    /// Write a note to the log.
+    Annotate {
+        text: String,
+    },
+    /// Flush the written events to the disk.
+    Flush,
+    /// Reload the privacy rules from the config file, the rest of the config
+    /// takes a restart.
+    ReloadPrivacy,
+    /// Currently focused window.
+    Current,
+}
+
+/// Daemon state, answer to [`Request::Status`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    pub paused: bool,
+    /// End of a timed pause.
+    pub paused_until: Option<DateTime<Utc>>,
+    /// Connected to sway.
+    pub connected: bool,
+    #[serde(flatten)]
+    pub activity: Snapshot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "result")]
+pub enum Response {
+    Ok,
+    Status(Status),
+    Current { focused: Option<Focused> },
+    Error { message: String },
+}
+
+impl Response {
+    pub fn error(message: impl ToString) -> Self {
+        Response::Error {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Request waiting for the daemon to answer it.
+#[derive(Debug)]
+pub struct ControlRequest {
+    pub request: Request,
+    reply: oneshot::Sender<Response>,
+}
+
+impl ControlRequest {
+    pub fn reply(self, response: Response) {
+        if self.reply.send(response).is_err() {
+            debug!("Control client has gone away.");
+        }
+    }
+}
+
+/// Bound control socket, the socket file is removed on drop.
+#[derive(Debug)]
+pub struct ControlSocket {
+    path: PathBuf,
+    listener: UnixListener,
+}
+
+impl ControlSocket {
+    /// Binds the socket at `path`, replacing a stale one. The daemon holds
+    /// the lock file, so nothing else is listening there.
+    pub async fn bind(path: impl AsRef<Path>) -> Result<Self, ControlError> {
+        let path = path.as_ref().to_path_buf();
+
+        if let Some(dir) = path.parent() {
+            tokio::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(dir)
+                .await?;
+        }
+
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => debug!("Removed the stale socket {:?}.", path),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let listener = UnixListener::bind(&path)?;
+
+        Ok(ControlSocket { path, listener })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Accepts the clients until cancelled, their requests are sent to
+    /// `requests`.
+    pub fn spawn(
+        self,
+        requests: mpsc::Sender<ControlRequest>,
+        token: CancellationToken,
+    ) -> JoinHandle<()> {
+        spawn(async move {
+            loop {
+                tokio::select! {
+                    accepted = self.listener.accept() => {
+                        match accepted {
+                            Ok((stream, _)) => {
+                                spawn(serve(stream, requests.clone()));
+                            }
+                            Err(err) => {
+                                warn!("Could not accept a control client: {}", err);
+                            }
+                        }
+                    },
+                    _ = token.cancelled() => {
+                        debug!("Closing the control socket.");
+                        break;
+                    },
+                }
+            }
+
+            drop(self);
+        })
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_file(&self.path) {
+            debug!("Could not remove {:?}: {}", self.path, err);
+        }
+    }
+}
+
+async fn serve(stream: UnixStream, requests: mpsc::Sender<ControlRequest>) {
+    let mut lines =
+        Framed::new(stream, LinesCodec::new_with_max_length(MAX_LINE));
+
+    while let Some(line) = lines.next().await {
+        let response = match line {
+            Ok(line) => match serde_json::from_str::<Request>(&line) {
+                Ok(request) => dispatch(&requests, request).await,
+                Err(err) => Response::error(format!("Bad request: {}", err)),
+            },
+            Err(err) => {
+                warn!("Dropping the control client: {}", err);
+                break;
+            }
+        };
+
+        let encoded = match serde_json::to_string(&response) {
+            Ok(encoded) => encoded,
+            Err(err) => {
+                warn!("Could not encode the control response: {}", err);
+                break;
+            }
+        };
+
+        if let Err(err) = lines.send(encoded).await {
+            debug!("Control client has gone away: {}", err);
+            break;
+        }
+    }
+}
+
+async fn dispatch(
+    requests: &mpsc::Sender<ControlRequest>,
+    request: Request,
+) -> Response {
+    let (reply, response) = oneshot::channel();
+
+    if requests
+        .send(ControlRequest { request, reply })
+        .await
+        .is_err()
+    {
+        return Response::error("Daemon is shutting down.");
+    }
+
+    response
+        .await
+        .unwrap_or_else(|_| Response::error("Daemon dropped the request."))
+}
+
+/// Sends a single request to the daemon at `path`.
+pub async fn send(
+    path: impl AsRef<Path>,
+    request: &Request,
+) -> Result<Response, ControlError> {
+    let stream = UnixStream::connect(path).await?;
+    let mut lines =
+        Framed::new(stream, LinesCodec::new_with_max_length(MAX_LINE));
+
+    lines.send(serde_json::to_string(request)?).await?;
+
+    let line = lines.next().await.ok_or(ControlError::Closed)??;
+    Ok(serde_json::from_str(&line)?)
+}
@@ -0,0 +1,306 @@
+use anyhow::{Context, Result, bail};
+use chrono::{Local, TimeDelta, Utc};
+use clap::{ArgMatches, Command, arg, command, value_parser};
+use matiane_core::category::Rules;
+use matiane_core::events::Focused;
+use matiane_core::import::activitywatch::Export;
+use matiane_core::import::import;
+use matiane_core::store::acquire_lock_file;
+use matiane_core::summary::format_duration;
+use matiane_core::xdg::Xdg;
+use std::path::{Path, PathBuf};
+use sway_matiane::bar::{BarFormat, I3Block, Waybar, i3bar_header};
+use sway_matiane::config::CtlConfig;
+use sway_matiane::control::{self, Request, Response, Status};
+use sway_matiane::privacy::PrivacyFilter;
+use tokio::time::{Duration, MissedTickBehavior, interval};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let matches = command!("matiane-ctl")
+        .about("Controls the running sway-matiane daemon")
+        .arg(
+            arg!(-s --socket <PATH> "Sets a custom control socket")
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(arg!(--json "Prints the raw JSON response"))
+        .subcommand_required(true)
+        .subcommand(Command::new("status").about("Shows the daemon state"))
+        .subcommand(
+            Command::new("pause").about("Pauses tracking").arg(
+                arg!([MINUTES] "Resumes after the minutes")
+                    .value_parser(value_parser!(u64)),
+            ),
+        )
+        .subcommand(Command::new("resume").about("Resumes tracking"))
+        .subcommand(
+            Command::new("annotate")
+                .about("Writes a note to the log")
+                .arg(arg!(<TEXT> ... "Text of the note")),
+        )
+        .subcommand(Command::new("flush").about("Flushes the log to the disk"))
+        .subcommand(
+            Command::new("reload-privacy").about(
+                "Reloads the privacy rules, other settings need a restart",
+            ),
+        )
+        .subcommand(Command::new("current").about("Shows the focused window"))
+        .subcommand(
+            Command::new("bar")
+                .about("Prints the status for Waybar or i3bar continuously")
+                .arg(
+                    arg!(-c --config <FILE> "Sets a custom config file")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(-f --format <FORMAT> "Sets the bar protocol")
+                        .value_parser(["waybar", "i3bar"]),
+                ),
+        )
+        .subcommand(
+            Command::new("import")
+                .about(
+                    "Imports an ActivityWatch export, the daemon must be \
+                     stopped",
+                )
+                .arg(
+                    arg!(-c --config <FILE> "Sets a custom config file")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(arg!(--host <HOST> "Imports the buckets of the host"))
+                .arg(
+                    arg!(<FILE> "JSON export of the window and AFK buckets")
+                        .value_parser(value_parser!(PathBuf)),
+                ),
+        )
+        .get_matches();
+
+    let socket = matches
+        .get_one::<PathBuf>("socket")
+        .cloned()
+        .unwrap_or_else(control::socket_path);
+
+    match matches.subcommand() {
+        Some(("bar", args)) => return run_bar(&socket, args).await,
+        Some(("import", args)) => return run_import(args).await,
+        _ => {}
+    }
+
+    let request = parse_request(&matches)?;
+    let response =
+        control::send(&socket, &request).await.with_context(|| {
+            format!("Could not reach the daemon at {:?}", socket)
+        })?;
+
+    if matches.get_flag("json") {
+        println!("{}", serde_json::to_string(&response)?);
+    }
+
+    match response {
+        Response::Error { message } => bail!("{}", message),
+        _ if matches.get_flag("json") => {}
+        Response::Ok => {}
+        Response::Status(status) => print_status(&status),
+        Response::Current { focused } => match focused {
+            Some(focused) => println!("{}", window(&focused)),
+            None => println!("No window has the focus."),
+        },
+    }
+
+    Ok(())
+}
+
+fn parse_request(matches: &ArgMatches) -> Result<Request> {
+    let request = match matches.subcommand() {
+        Some(("status", _)) => Request::Status,
+        Some(("pause", args)) => Request::Pause {
+            minutes: args.get_one::<u64>("MINUTES").copied(),
+        },
+        Some(("resume", _)) => Request::Resume,
+        Some(("annotate", args)) => Request::Annotate {
+            text: args
+                .get_many::<String>("TEXT")
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        },
+        Some(("flush", _)) => Request::Flush,
+        Some(("reload-privacy", _)) => Request::ReloadPrivacy,
+        Some(("current", _)) => Request::Current,
+        _ => bail!("Unknown command."),
+    };
+
+    Ok(request)
+}
+
+/// Polls the daemon and prints a status line on every update.
+async fn run_bar(socket: &Path, args: &ArgMatches) -> Result<()> {
+    let cfg = load_config(&config_file(args)).await?;
+    let rules = Rules::new(&cfg.categories)
+        .context("Failed to load the category rules")?;
+
+    let format = match args.get_one::<String>("format").map(String::as_str) {
+        Some("waybar") => BarFormat::Waybar,
+        Some("i3bar") => BarFormat::I3bar,
+        _ => cfg.bar.format,
+    };
+    let goal = TimeDelta::minutes(cfg.bar.daily_goal.into());
+
+    let mut ticks =
+        interval(Duration::from_secs(cfg.bar.interval.max(1).into()));
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    if format == BarFormat::I3bar {
+        println!("{}", i3bar_header());
+    }
+
+    let mut separator = "";
+    let mut last = None;
+
+    loop {
+        ticks.tick().await;
+
+        let status = match control::send(socket, &Request::Status).await {
+            Ok(Response::Status(status)) => Some(status),
+            _ => None,
+        };
+
+        let waybar = Waybar::new(status.as_ref(), &rules, goal, Utc::now());
+        let line = match format {
+            BarFormat::Waybar => serde_json::to_string(&waybar)?,
+            BarFormat::I3bar => format!(
+                "{}[{}]",
+                separator,
+                serde_json::to_string(&I3Block::from(waybar))?
+            ),
+        };
+
+        // Waybar redraws on every line.
+        if last.as_ref() == Some(&line) {
+            continue;
+        }
+
+        println!("{}", line);
+        last = Some(line);
+        separator = ",";
+    }
+}
+
+/// Writes the ActivityWatch history into the log, next to what is there.
+async fn run_import(args: &ArgMatches) -> Result<()> {
+    let cfg = load_config(&config_file(args)).await?;
+    let privacy = PrivacyFilter::new(&cfg.sway.privacy)
+        .context("Failed to load the privacy rules")?;
+
+    let file = args
+        .get_one::<PathBuf>("FILE")
+        .context("Missing the export file")?;
+    let input = tokio::fs::read_to_string(file)
+        .await
+        .with_context(|| format!("Failed to read {:?}", file))?;
+
+    let export = Export::parse(&input)?;
+    let host = args.get_one::<String>("host").map(String::as_str);
+    let mut intervals = export.intervals(host)?;
+
+    for interval in &mut intervals {
+        interval.focused = interval
+            .focused
+            .take()
+            .and_then(|focused| privacy.apply(focused));
+    }
+
+    let state_dir = cfg.general.state_dir.clone();
+    tokio::fs::create_dir_all(&state_dir).await?;
+    let lockfile = acquire_lock_file(state_dir.clone())
+        .await
+        .context("Could not lock the log, is sway-matiane running?")?;
+
+    let summary =
+        import(&state_dir, &intervals, cfg.general.live_interval()).await?;
+    drop(lockfile);
+
+    match (summary.dates.first(), summary.dates.last()) {
+        (Some(first), Some(last)) => println!(
+            "Imported {} events into {} day logs, {} to {}.",
+            summary.events,
+            summary.dates.len(),
+            first,
+            last
+        ),
+        _ => println!("Nothing to import."),
+    }
+
+    if summary.overlap > TimeDelta::zero() {
+        println!(
+            "Skipped {} the log already covers.",
+            format_duration(summary.overlap)
+        );
+    }
+
+    Ok(())
+}
+
+fn config_file(args: &ArgMatches) -> PathBuf {
+    args.get_one::<PathBuf>("config")
+        .cloned()
+        .unwrap_or_else(|| {
+            Xdg::new(matiane_core::NAME.into())
+                .config_dir()
+                .join("config.toml")
+        })
+}
+
+async fn load_config(file: &PathBuf) -> Result<CtlConfig> {
+    let file_str = match tokio::fs::read_to_string(file).await {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(CtlConfig::default());
+        }
+        Err(e) => return Err(e).context("Failed to read configuration file"),
+    };
+
+    let mut parsed = toml::from_str::<CtlConfig>(&file_str)
+        .context("Failed to parse TOML from configuration file")?;
+    parsed
+        .general
+        .apply_deprecated(&file_str)
+        .context("Failed to parse TOML from configuration file")?;
+
+    Ok(parsed)
+}
+
+fn window(focused: &Focused) -> String {
+    format!("{} — {}", focused.id, focused.title)
+}
+
+fn print_status(status: &Status) {
+    match status.paused_until {
+        Some(until) if status.paused => println!(
+            "Tracking: paused until {}",
+            until.with_timezone(&Local).format("%H:%M")
+        ),
+        _ if status.paused => println!("Tracking: paused"),
+        _ => println!("Tracking: active"),
+    }
+
+    match status.connected {
+        true => println!("Sway: connected"),
+        false => println!("Sway: disconnected"),
+    }
+
+    if let Some(focused) = &status.activity.focused {
+        match status.activity.since {
+            Some(since) => println!(
+                "Focused: {} ({})",
+                window(focused),
+                format_duration(Utc::now() - since)
+            ),
+            None => println!("Focused: {}", window(focused)),
+        }
+    }
+
+    println!("Today: {}", format_duration(status.activity.today));
+}
@@ -0,0 +1,87 @@
+use crate::sway::client::{ClientError, SwayClient};
+use crate::sway::reply::Node;
+use crate::sway::tree::{Location, find_focused, find_presence, locate};
+use log::{debug, warn};
+use matiane_core::events::{Event, Focused};
+
+/// Builds the focus event from the sway container and where it is.
+pub fn focused_from_node(container: &Node, location: Location) -> Focused {
+    let app_id = container.app_id.clone().or_else(|| {
+        let win_props = container.window_properties.as_ref()?;
+        win_props
+            .instance
+            .clone()
+            .or_else(|| win_props.class.clone())
+    });
+
+    let title = container.name.clone().or_else(|| app_id.clone());
+
+    Focused {
+        title: title.unwrap_or_else(|| "title-not-found".to_string()),
+        id: app_id.unwrap_or_else(|| "app-id-not-found".to_string()),
+        pid: container.pid.unwrap_or(0),
+        workspace: location.workspace,
+        workspace_num: location.workspace_num,
+        output: location.output,
+        executable: container.pid.and_then(proc_executable),
+    }
+}
+
+/// Executable of a running process, recorded with the focus while the pid
+/// still belongs to the window.
+fn proc_executable(pid: i32) -> Option<String> {
+    if pid <= 0 {
+        return None;
+    }
+
+    let path = std::fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+    path.into_os_string().into_string().ok()
+}
+
+/// Focus event for the container of a window event, the workspace and the
+/// output are looked up in the tree.
+pub async fn window_focus(
+    client: &mut SwayClient,
+    container: &Node,
+) -> Focused {
+    let location = match client.get_tree().await {
+        Ok(tree) => locate(&tree, container.id).unwrap_or_default(),
+        Err(err) => {
+            warn!("Could not find the workspace of the window: {}", err);
+            Location::default()
+        }
+    };
+
+    focused_from_node(container, location)
+}
+
+/// Focus event for the currently focused window, nothing when the focus is
+/// on an empty workspace.
+pub async fn current_focus(
+    client: &mut SwayClient,
+) -> Result<Option<Focused>, ClientError> {
+    let tree = client.get_tree().await?;
+
+    Ok(find_focused(&tree).map(|node| {
+        focused_from_node(node, locate(&tree, node.id).unwrap_or_default())
+    }))
+}
+
+/// `Passive` instead of `Idle` while a view keeps the user present.
+pub async fn idle_event(client: &mut SwayClient) -> Event {
+    let tree = match client.get_tree().await {
+        Ok(tree) => tree,
+        Err(err) => {
+            warn!("Could not check the idle inhibitors: {}", err);
+            return Event::Idle;
+        }
+    };
+
+    match find_presence(&tree) {
+        Some(node) => {
+            debug!("{:?} keeps the user present.", node.app_id);
+            Event::Passive
+        }
+        None => Event::Idle,
+    }
+}
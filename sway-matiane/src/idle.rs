@@ -0,0 +1,205 @@
+// https://wayland.app/protocols/ext-idle-notify-v1
+// https://wayland.app/protocols/kde-idle
+
+use log::{debug, warn};
+use matiane_core::events::Event;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::mpsc::{
+    UnboundedReceiver, UnboundedSender, unbounded_channel,
+};
+use wayland_client::globals::{
+    BindError, GlobalError, GlobalListContents, registry_queue_init,
+};
+use wayland_client::protocol::{wl_registry, wl_seat};
+use wayland_client::{
+    ConnectError, Connection, Dispatch, DispatchError, EventQueue, Proxy,
+    QueueHandle,
+};
+use wayland_protocols::ext::idle_notify::v1::client::{
+    ext_idle_notification_v1::{self, ExtIdleNotificationV1},
+    ext_idle_notifier_v1::ExtIdleNotifierV1,
+};
+use wayland_protocols_plasma::idle::client::{
+    org_kde_kwin_idle::OrgKdeKwinIdle,
+    org_kde_kwin_idle_timeout::{self, OrgKdeKwinIdleTimeout},
+};
+
+#[derive(Debug, Error)]
+pub enum IdleError {
+    #[error("Could not connect to wayland: {0}")]
+    Connect(#[from] ConnectError),
+    #[error("Could not list the wayland globals: {0}")]
+    Globals(#[from] GlobalError),
+    #[error("Could not bind the seat: {0}")]
+    Seat(#[source] BindError),
+    #[error("Compositor supports neither ext-idle-notify nor kde-idle.")]
+    Unsupported,
+    #[error("Wayland dispatch failed: {0}")]
+    Dispatch(#[from] DispatchError),
+}
+
+/// Protocol used to get the idle notifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleProtocol {
+    ExtIdleNotify,
+    KdeIdle,
+}
+
+struct IdleState {
+    sender: UnboundedSender<Event>,
+}
+
+impl IdleState {
+    fn send(&self, event: Event) {
+        if self.sender.send(event).is_err() {
+            debug!("Idle receiver has been dropped.");
+        }
+    }
+}
+
+/// Idle notifications read from the compositor on a separate thread, the
+/// events stop when the compositor goes away.
+pub struct IdleNotify {
+    pub protocol: IdleProtocol,
+    pub events: UnboundedReceiver<Event>,
+    _handle: JoinHandle<()>,
+}
+
+impl IdleNotify {
+    /// Prefers `ext_idle_notifier_v1` and falls back to `org_kde_kwin_idle`.
+    pub fn spawn(timeout: Duration) -> Result<Self, IdleError> {
+        let connection = Connection::connect_to_env()?;
+        let (globals, mut queue) =
+            registry_queue_init::<IdleState>(&connection)?;
+        let qh = queue.handle();
+
+        let seat: wl_seat::WlSeat =
+            globals.bind(&qh, 1..=9, ()).map_err(IdleError::Seat)?;
+        let timeout_ms = timeout.as_millis().try_into().unwrap_or(u32::MAX);
+
+        let protocol = if let Ok(notifier) =
+            globals.bind::<ExtIdleNotifierV1, _, _>(&qh, 1..=1, ())
+        {
+            notifier.get_idle_notification(timeout_ms, &seat, &qh, ());
+            IdleProtocol::ExtIdleNotify
+        } else if let Ok(idle) =
+            globals.bind::<OrgKdeKwinIdle, _, _>(&qh, 1..=1, ())
+        {
+            idle.get_idle_timeout(&seat, timeout_ms, &qh, ());
+            IdleProtocol::KdeIdle
+        } else {
+            return Err(IdleError::Unsupported);
+        };
+
+        let (sender, events) = unbounded_channel();
+        let mut state = IdleState { sender };
+
+        // Makes sure the requests went through before the thread starts.
+        queue.roundtrip(&mut state)?;
+
+        let handle = thread::spawn(move || run(queue, state));
+
+        Ok(IdleNotify {
+            protocol,
+            events,
+            _handle: handle,
+        })
+    }
+}
+
+fn run(mut queue: EventQueue<IdleState>, mut state: IdleState) {
+    while !state.sender.is_closed() {
+        if let Err(err) = queue.blocking_dispatch(&mut state) {
+            warn!("Idle notifications stopped: {}", err);
+            break;
+        }
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for IdleState {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for IdleState {
+    fn event(
+        _: &mut Self,
+        _: &wl_seat::WlSeat,
+        _: wl_seat::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotifierV1, ()> for IdleState {
+    fn event(
+        _: &mut Self,
+        _: &ExtIdleNotifierV1,
+        _: <ExtIdleNotifierV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotificationV1, ()> for IdleState {
+    fn event(
+        state: &mut Self,
+        _: &ExtIdleNotificationV1,
+        event: ext_idle_notification_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_idle_notification_v1::Event::Idled => state.send(Event::Idle),
+            ext_idle_notification_v1::Event::Resumed => {
+                state.send(Event::Active)
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<OrgKdeKwinIdle, ()> for IdleState {
+    fn event(
+        _: &mut Self,
+        _: &OrgKdeKwinIdle,
+        _: <OrgKdeKwinIdle as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<OrgKdeKwinIdleTimeout, ()> for IdleState {
+    fn event(
+        state: &mut Self,
+        _: &OrgKdeKwinIdleTimeout,
+        event: org_kde_kwin_idle_timeout::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            org_kde_kwin_idle_timeout::Event::Idle => state.send(Event::Idle),
+            org_kde_kwin_idle_timeout::Event::Resumed => {
+                state.send(Event::Active)
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,4 +1,12 @@
+pub mod activity;
+pub mod bar;
 pub mod config;
+pub mod control;
+pub mod focus;
+pub mod idle;
+pub mod logind;
+pub mod privacy;
+pub mod supervisor;
 pub mod sway;
 pub mod swayidle;
 pub mod tray;
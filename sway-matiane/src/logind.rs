@@ -0,0 +1,157 @@
+// https://www.freedesktop.org/software/systemd/man/latest/org.freedesktop.login1.html
+
+use futures::future;
+use futures::stream::{self, BoxStream, StreamExt};
+use log::{debug, warn};
+use matiane_core::events::Event;
+use thiserror::Error;
+use zbus::{Connection, proxy};
+
+#[derive(Debug, Error)]
+pub enum LogindError {
+    #[error("DBus error: {0}")]
+    DBus(#[from] zbus::Error),
+}
+
+#[proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+pub trait Manager {
+    fn get_session(
+        &self,
+        session_id: &str,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    #[zbus(signal)]
+    fn prepare_for_sleep(&self, start: bool) -> zbus::Result<()>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1"
+)]
+pub trait Session {
+    #[zbus(signal)]
+    fn lock(&self) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn unlock(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn idle_hint(&self) -> zbus::Result<bool>;
+}
+
+/// Signals that are turned into events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signals {
+    /// `PrepareForSleep` and the session `Lock`/`Unlock`, the session wakes
+    /// up once it is unlocked too.
+    pub sleep: bool,
+    /// Session `IdleHint` changes.
+    pub idle_hint: bool,
+}
+
+/// Sleep or lock signal, `true` when it starts.
+#[derive(Debug, Clone, Copy)]
+enum Away {
+    Sleep(bool),
+    Lock(bool),
+}
+
+/// The session is awake only while it is neither asleep nor locked, e.g.
+/// waking up from a suspend with the screen locked is not.
+#[derive(Debug, Default)]
+struct AwayState {
+    asleep: bool,
+    locked: bool,
+}
+
+impl AwayState {
+    fn is_awake(&self) -> bool {
+        !self.asleep && !self.locked
+    }
+
+    fn update(&mut self, away: Away) -> Option<Event> {
+        let was_awake = self.is_awake();
+
+        match away {
+            Away::Sleep(asleep) => self.asleep = asleep,
+            Away::Lock(locked) => self.locked = locked,
+        }
+
+        match (was_awake, self.is_awake()) {
+            (true, false) => Some(Event::Sleep),
+            (false, true) => Some(Event::Awake),
+            _ => None,
+        }
+    }
+}
+
+/// Session of the daemon, `auto` lets logind pick the caller's session.
+pub fn session_id() -> String {
+    std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".into())
+}
+
+/// Sleep, lock and idle events of the session.
+pub async fn watch(
+    connection: &Connection,
+    session_id: &str,
+    signals: Signals,
+) -> Result<BoxStream<'static, Event>, LogindError> {
+    let manager = ManagerProxy::new(connection).await?;
+    let session_path = manager.get_session(session_id).await?;
+    debug!("Watching logind session {}.", session_path.as_str());
+
+    let session = SessionProxy::builder(connection)
+        .path(session_path)?
+        .build()
+        .await?;
+
+    let mut streams: Vec<BoxStream<'static, Event>> = vec![];
+
+    if signals.sleep {
+        let sleep = manager.receive_prepare_for_sleep().await?.filter_map(
+            |signal| async move {
+                match signal.args() {
+                    Ok(args) => Some(Away::Sleep(args.start)),
+                    Err(err) => {
+                        warn!("Bad PrepareForSleep signal: {}", err);
+                        None
+                    }
+                }
+            },
+        );
+        let lock = session.receive_lock().await?.map(|_| Away::Lock(true));
+        let unlock = session.receive_unlock().await?.map(|_| Away::Lock(false));
+
+        let away =
+            stream::select_all([sleep.boxed(), lock.boxed(), unlock.boxed()])
+                .scan(AwayState::default(), |state, away| {
+                    future::ready(Some(state.update(away)))
+                })
+                .filter_map(future::ready);
+
+        streams.push(away.boxed());
+    }
+
+    if signals.idle_hint {
+        let idle = session.receive_idle_hint_changed().await.filter_map(
+            |changed| async move {
+                match changed.get().await {
+                    Ok(true) => Some(Event::Idle),
+                    Ok(false) => Some(Event::Active),
+                    Err(err) => {
+                        warn!("Bad IdleHint value: {}", err);
+                        None
+                    }
+                }
+            },
+        );
+
+        streams.push(idle.boxed());
+    }
+
+    Ok(stream::select_all(streams).boxed())
+}
@@ -1,29 +1,48 @@
 #![cfg(target_os = "linux")]
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, TimeDelta, Utc};
 use clap::{
     arg,
     builder::{PossibleValuesParser, TypedValueParser},
     command, value_parser,
 };
-use futures::{StreamExt, future::ready};
-use log::{LevelFilter, debug, error, info, trace, warn};
+use futures::{StreamExt, future::pending, stream::BoxStream};
+use log::{LevelFilter, debug, info, trace, warn};
 use matiane_core::events::{Event, Focused, TimedEvent};
 use matiane_core::log::init_global_logger;
 use matiane_core::process::RunningHandle;
 use matiane_core::store::{EventWriter, acquire_lock_file};
-use matiane_core::xdg::Xdg;
+use matiane_core::xdg::{self, Xdg};
 use std::path::PathBuf;
+use std::pin::Pin;
 use std::str::FromStr;
+use sway_matiane::activity::Activity;
+use sway_matiane::config::{IdleBackend, SleepBackend};
+use sway_matiane::control::{self, ControlSocket, Request, Response, Status};
+use sway_matiane::focus::{current_focus, idle_event, window_focus};
+use sway_matiane::idle::IdleNotify;
+use sway_matiane::logind::{self, LogindError};
+use sway_matiane::privacy::PrivacyFilter;
+use sway_matiane::supervisor::{Backoff, SwayConnection};
+use sway_matiane::tray::{TrayCommand, TrayStatus};
 use sway_matiane::{config, sway, swayidle, tray};
 use tokio::signal::unix::{SignalKind, signal};
-use tokio::time::{MissedTickBehavior, interval};
+use tokio::sync::{mpsc, watch};
+use tokio::time::{
+    Duration, Instant, MissedTickBehavior, Sleep, interval, sleep,
+};
 use tokio_util::sync::CancellationToken;
 
 use sway::{
-    command::EventType, connection::subscribe, reply::Event as SwayEvent,
+    client::SwayClient, connection::SubscribeError, reply::Event as SwayEvent,
 };
 
+/// How often the tray catches up with the time spent.
+const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Control requests waiting for the main loop.
+const CONTROL_QUEUE: usize = 16;
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let xdg = Xdg::new(matiane_core::NAME.into());
@@ -39,68 +58,132 @@ async fn main() -> Result<()> {
     let cfg = load_config(&config_file).await?;
     trace!("Config: {:?}", cfg);
 
+    let mut privacy = PrivacyFilter::new(&cfg.sway.privacy)
+        .context("Failed to load the privacy rules")?;
+
     let swaysock_path: PathBuf = std::env::var("SWAYSOCK")
         .with_context(|| "Could not find swaysock env var.")?
         .into();
 
-    let state_dir = cfg.general.state_dir;
+    let state_dir = cfg.general.state_dir.clone();
     let now = Utc::now();
 
     debug!("Acquiring lockfile...");
     let lockfile = acquire_lock_file(state_dir.clone()).await?;
 
     debug!("Opening store...");
-    let mut write_store = EventWriter::open(state_dir, now).await?;
+    let write_store = EventWriter::open(state_dir.clone(), now).await?;
+
+    debug!("Reading today's activity...");
+    let live_interval = cfg.general.live_interval();
+    let passive_as_active = cfg.general.passive_as_active;
+    let activity =
+        match Activity::load(state_dir, live_interval, passive_as_active, now)
+            .await
+        {
+            Ok(activity) => activity,
+            Err(err) => {
+                warn!("Could not read today's activity: {}", err);
+                Activity::new(live_interval, passive_as_active, now)
+            }
+        };
+
+    let mut recorder = Recorder {
+        store: write_store,
+        activity,
+        paused: false,
+        paused_until: None,
+    };
 
-    debug!("Running swayidle...");
     info!("Idle timoeut is set to: {} seconds.", cfg.sway.idle_timeout);
+    let mut idle_notify = match cfg.sway.idle_backend {
+        IdleBackend::Wayland => {
+            let timeout = Duration::from_secs(cfg.sway.idle_timeout.into());
+
+            match IdleNotify::spawn(timeout) {
+                Ok(notify) => {
+                    info!(
+                        "Using {:?} for idle notifications.",
+                        notify.protocol
+                    );
+                    Some(notify)
+                }
+                Err(err) => {
+                    warn!("{} Falling back to swayidle.", err);
+                    None
+                }
+            }
+        }
+        IdleBackend::Swayidle | IdleBackend::Logind => None,
+    };
+
+    let signals = logind::Signals {
+        sleep: cfg.sway.sleep_backend == SleepBackend::Logind,
+        idle_hint: cfg.sway.idle_backend == IdleBackend::Logind,
+    };
+    let mut logind_events = if signals.sleep || signals.idle_hint {
+        match watch_logind(signals).await {
+            Ok(events) => Some(events),
+            Err(err) => {
+                warn!("{} Falling back to swayidle.", err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // swayidle covers whatever the other backends do not.
+    let logind_sleep = logind_events.is_some() && signals.sleep;
+    let logind_idle = logind_events.is_some() && signals.idle_hint;
+    let swayidle_timeout = (idle_notify.is_none() && !logind_idle)
+        .then_some(cfg.sway.idle_timeout);
+
     let cancel_tok = CancellationToken::new();
-    let sway_idle = run_swayidle(cfg.sway.idle_timeout, cancel_tok.clone())?;
+    let sway_idle =
+        run_swayidle(swayidle_timeout, !logind_sleep, cancel_tok.clone())?;
 
     debug!("Opening swaysocket...");
-    let events = subscribe(&swaysock_path, EventType::Window).await?;
-    let mut alive_interval = interval(cfg.sway.live_interval);
+    let mut connection = SwayConnection::open(&swaysock_path).await?;
+    let runtime_dir = xdg::runtime_dir(None::<&str>);
+    let mut backoff = Backoff::default();
+
+    // Nothing is written until the focus changes otherwise.
+    write_current_focus(&mut connection.client, &privacy, &mut recorder)
+        .await?;
+
+    let mut sway = Some(connection);
+    let reconnect = sleep(Duration::ZERO);
+    tokio::pin!(reconnect);
+
+    let mut alive_interval = interval(cfg.general.live_interval());
     alive_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
+    // Ends a timed pause, armed while `paused_until` is set.
+    let pause_timer = sleep(Duration::ZERO);
+    tokio::pin!(pause_timer);
+
     debug!("Showing tray...");
-    let _tray = tray::spawn_tray(cancel_tok.clone());
+    let (command_tx, mut tray_commands) = mpsc::unbounded_channel();
+    let (tray_status, status_rx) = watch::channel(recorder.status());
+    let mut status_interval = interval(STATUS_INTERVAL);
+    status_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let _tray = tray::spawn_tray(status_rx, command_tx, cancel_tok.clone());
+
+    let (request_tx, mut control_requests) = mpsc::channel(CONTROL_QUEUE);
+    let _control = match ControlSocket::bind(control::socket_path()).await {
+        Ok(socket) => {
+            debug!("Listening for control on {:?}.", socket.path());
+            Some(socket.spawn(request_tx, cancel_tok.clone()))
+        }
+        Err(err) => {
+            warn!("Control socket is not available: {}", err);
+            None
+        }
+    };
 
     info!("Mematiane has started!");
 
-    // Transform sway event into matiane event.
-    let mut mematiene_events = events
-        .filter(|event| match event {
-            Ok(SwayEvent::Window(_)) => ready(true),
-            Ok(_) => ready(false),
-            Err(err) => {
-                warn!("Sway event returned an error {:?}", err);
-                ready(false)
-            }
-        })
-        .map(|event| {
-            let SwayEvent::Window(mut win_event) = event? else {
-                // must not happen, maybe rewrite to return concrete type?
-                return Err(anyhow::anyhow!("Incorrect sway event type!"));
-            };
-
-            let app_id = win_event.container.app_id.take().or_else(|| {
-                let win_props = win_event.container.window_properties.take()?;
-                win_props.instance.or(win_props.class)
-            });
-
-            let title =
-                win_event.container.name.take().or_else(|| app_id.clone());
-            let pid = win_event.container.pid.unwrap_or(0);
-
-            let matiane_event = Box::new(Focused {
-                title: title.unwrap_or_else(|| "title-not-found".to_string()),
-                id: app_id.unwrap_or_else(|| "app-id-not-found".to_string()),
-                pid,
-            });
-
-            Ok::<Event, anyhow::Error>(Event::Focused(matiane_event))
-        });
-
     let mut sigusr1 = signal(SignalKind::user_defined1())?;
     let mut sigusr2 = signal(SignalKind::user_defined2())?;
     let mut idle = signal(SignalKind::from_raw(libc::SIGRTMIN() + 1))?;
@@ -108,46 +191,222 @@ async fn main() -> Result<()> {
 
     loop {
         tokio::select! {
-            event = mematiene_events.next() => {
+            event = next_event(&mut sway) => {
+                let event = match event {
+                    Some(Ok(SwayEvent::Window(win_event))) => {
+                        let Some(connection) = sway.as_mut() else {
+                            continue;
+                        };
+
+                        let focused = window_focus(
+                            &mut connection.client,
+                            &win_event.container,
+                        )
+                        .await;
+                        Ok(focus_event(&privacy, focused))
+                    },
+                    Some(Ok(SwayEvent::Shutdown(_))) => {
+                        Err("Sway is exiting.".to_owned())
+                    },
+                    Some(Ok(_)) => continue,
+                    // A single undecodable event is not worth a reconnect.
+                    Some(Err(
+                        err @ (SubscribeError::BadPayload(_)
+                        | SubscribeError::BadCommand(_)
+                        | SubscribeError::NotAnEvent(_)),
+                    )) => {
+                        warn!("Sway event returned an error {:?}", err);
+                        continue;
+                    },
+                    Some(Err(err)) => {
+                        Err(format!("Sway event returned an error {:?}", err))
+                    },
+                    None => Err("Sway socket has been closed.".to_owned()),
+                };
+
                 match event {
-                    Some(Ok(event)) => {
+                    Ok(event) => {
                         trace!("Received an event.");
-                        write_store.write(&timed_event(event)).await?;
+                        recorder.record(event).await?;
                     }
-                    Some(Err(err)) => {
-                        error!("Received errored event: {:?}", err);
-                        break;
-                    },
-                    None => {
-                        error!("Sway socket has been closed.");
-                        break;
+                    Err(reason) => {
+                        warn!("{} Reconnecting...", reason);
+                        sway = None;
+                        recorder.record(Event::Disconnected).await?;
+                        reconnect
+                            .as_mut()
+                            .reset(Instant::now() + backoff.next_delay());
                     },
                 };
             },
 
+            _ = &mut reconnect, if sway.is_none() => {
+                match SwayConnection::reconnect(&swaysock_path, &runtime_dir)
+                    .await
+                {
+                    Ok(mut connection) => {
+                        info!("Reconnected to sway on {:?}.", connection.path);
+                        backoff.reset();
+                        write_current_focus(
+                            &mut connection.client,
+                            &privacy,
+                            &mut recorder,
+                        )
+                        .await?;
+                        sway = Some(connection);
+                    }
+                    Err(err) => {
+                        let delay = backoff.next_delay();
+                        warn!(
+                            "Could not reconnect to sway: {}, retrying in {:?}.",
+                            err, delay
+                        );
+                        reconnect.as_mut().reset(Instant::now() + delay);
+                    }
+                }
+            },
+
             _ = alive_interval.tick() => {
                 trace!("Live tick.");
-                write_store.write(&timed_event(Event::Alive)).await?;
+                recorder.record(Event::Alive).await?;
+            },
+
+            command = tray_commands.recv() => {
+                let Some(command) = command else {
+                    continue;
+                };
+
+                debug!("Tray command: {:?}", command);
+                match command {
+                    TrayCommand::Pause(duration) => {
+                        pause_tracking(
+                            &mut recorder,
+                            duration,
+                            pause_timer.as_mut(),
+                        )
+                        .await?;
+                    },
+                    TrayCommand::Resume => {
+                        resume_tracking(&mut sway, &privacy, &mut recorder)
+                            .await?;
+                    },
+                    TrayCommand::Open => open_viewer(),
+                    TrayCommand::Quit => {
+                        debug!("Quit from the tray.");
+                        cancel_tok.cancel();
+                        break;
+                    },
+                }
+            },
+
+            _ = &mut pause_timer, if recorder.paused_until.is_some() => {
+                info!("Pause is over.");
+                resume_tracking(&mut sway, &privacy, &mut recorder).await?;
+            },
+
+            request = control_requests.recv() => {
+                let Some(request) = request else {
+                    continue;
+                };
+
+                debug!("Control request: {:?}", request.request);
+                let response = match request.request.clone() {
+                    Request::Status => Response::Status(recorder.control_status(
+                        sway.is_some(),
+                    )),
+                    Request::Current => Response::Current {
+                        focused: recorder.status().activity.focused,
+                    },
+                    Request::Pause { minutes } => {
+                        let duration = minutes
+                            .map(|minutes| Duration::from_secs(minutes.saturating_mul(60)));
+                        pause_tracking(
+                            &mut recorder,
+                            duration,
+                            pause_timer.as_mut(),
+                        )
+                        .await?;
+                        Response::Ok
+                    },
+                    Request::Resume => {
+                        resume_tracking(&mut sway, &privacy, &mut recorder)
+                            .await?;
+                        Response::Ok
+                    },
+                    Request::Annotate { text } => {
+                        recorder.record(Event::Annotation(text)).await?;
+                        Response::Ok
+                    },
+                    Request::Flush => match recorder.store.flush().await {
+                        Ok(()) => Response::Ok,
+                        Err(err) => Response::error(err),
+                    },
+                    Request::ReloadPrivacy => match reload_privacy(&config_file)
+                        .await
+                    {
+                        Ok(reloaded) => {
+                            info!("Reloaded the privacy rules.");
+                            privacy = reloaded;
+                            Response::Ok
+                        }
+                        Err(err) => Response::error(format!("{:#}", err)),
+                    },
+                };
+
+                request.reply(response);
+            },
+
+            _ = status_interval.tick() => {
+                trace!("Status tick.");
             },
 
             _ = sigusr1.recv() => {
                 debug!("Sleeping or locking...");
-                write_store.write(&timed_event(Event::Sleep)).await?;
+                recorder.record(Event::Sleep).await?;
             },
 
             _ = sigusr2.recv() => {
                 debug!("Waking up or unlocking...");
-                write_store.write(&timed_event(Event::Awake)).await?;
+                recorder.record(Event::Awake).await?;
+            },
+
+            event = next_idle_event(&mut idle_notify) => {
+                match event {
+                    Some(event) => {
+                        debug!("Idle notification: {:?}", event);
+                        let event = check_presence(event, &mut sway).await;
+                        recorder.record(event).await?;
+                    }
+                    None => {
+                        warn!("Idle notifications have stopped.");
+                        idle_notify = None;
+                    }
+                }
+            },
+
+            event = next_logind_event(&mut logind_events) => {
+                match event {
+                    Some(event) => {
+                        debug!("Logind notification: {:?}", event);
+                        let event = check_presence(event, &mut sway).await;
+                        recorder.record(event).await?;
+                    }
+                    None => {
+                        warn!("Logind notifications have stopped.");
+                        logind_events = None;
+                    }
+                }
             },
 
             _ = idle.recv() => {
                 debug!("Idle for {} seconds.", cfg.sway.idle_timeout);
-                write_store.write(&timed_event(Event::Idle)).await?;
+                let event = check_presence(Event::Idle, &mut sway).await;
+                recorder.record(event).await?;
             },
 
             _ = resume.recv() => {
                 debug!("Resumed.");
-                write_store.write(&timed_event(Event::Active)).await?;
+                recorder.record(Event::Active).await?;
             },
 
             _ = tokio::signal::ctrl_c() => {
@@ -156,6 +415,13 @@ async fn main() -> Result<()> {
                 break;
             },
         }
+
+        let status = recorder.status();
+        tray_status.send_if_modified(|current| {
+            let modified = *current != status;
+            *current = status;
+            modified
+        });
     }
 
     info!("Closing matiane...");
@@ -211,12 +477,206 @@ async fn load_config(file: &PathBuf) -> Result<config::SwayCliConfig> {
         Err(e) => return Err(e).context("Failed to read configuration file"),
     };
 
-    let parsed = toml::from_str::<config::SwayCliConfig>(&file_str)
+    let mut parsed = toml::from_str::<config::SwayCliConfig>(&file_str)
+        .context("Failed to parse TOML from configuration file")?;
+    parsed
+        .general
+        .apply_deprecated(&file_str)
         .context("Failed to parse TOML from configuration file")?;
 
     Ok(parsed)
 }
 
+async fn next_event(
+    sway: &mut Option<SwayConnection>,
+) -> Option<Result<SwayEvent, SubscribeError>> {
+    match sway {
+        Some(connection) => connection.events.next().await,
+        None => pending().await,
+    }
+}
+
+async fn next_idle_event(
+    idle_notify: &mut Option<IdleNotify>,
+) -> Option<Event> {
+    match idle_notify {
+        Some(notify) => notify.events.recv().await,
+        None => pending().await,
+    }
+}
+
+async fn watch_logind(
+    signals: logind::Signals,
+) -> Result<BoxStream<'static, Event>, LogindError> {
+    let connection = zbus::Connection::system().await?;
+    let session_id = logind::session_id();
+
+    logind::watch(&connection, &session_id, signals).await
+}
+
+async fn next_logind_event(
+    events: &mut Option<BoxStream<'static, Event>>,
+) -> Option<Event> {
+    match events {
+        Some(events) => events.next().await,
+        None => pending().await,
+    }
+}
+
+/// Idle turns into passive presence when sway shows the user is watching.
+async fn check_presence(
+    event: Event,
+    sway: &mut Option<SwayConnection>,
+) -> Event {
+    match (event, sway) {
+        (Event::Idle, Some(connection)) => {
+            idle_event(&mut connection.client).await
+        }
+        (event, _) => event,
+    }
+}
+
+/// Focus event that went through the privacy rules.
+fn focus_event(privacy: &PrivacyFilter, focused: Focused) -> Event {
+    match privacy.apply(focused) {
+        Some(focused) => Event::Focused(Box::new(focused)),
+        None => Event::Private,
+    }
+}
+
+/// Writes the events and keeps today's activity in step with the log.
+struct Recorder {
+    store: EventWriter,
+    activity: Activity,
+    paused: bool,
+    /// End of a timed pause.
+    paused_until: Option<DateTime<Utc>>,
+}
+
+impl Recorder {
+    /// Writes the event unless tracking is paused. The daemon's own
+    /// bookkeeping is still written while paused.
+    async fn record(&mut self, event: Event) -> Result<()> {
+        let bookkeeping = matches!(
+            event,
+            Event::Alive
+                | Event::Paused
+                | Event::Resumed
+                | Event::Disconnected
+                | Event::Annotation(_)
+        );
+
+        if self.paused && !bookkeeping {
+            trace!("Paused, dropping {:?}.", event);
+            return Ok(());
+        }
+
+        let event = timed_event(event);
+        self.store.write(&event).await?;
+        self.activity.push(&event);
+
+        Ok(())
+    }
+
+    fn status(&mut self) -> TrayStatus {
+        TrayStatus {
+            paused: self.paused,
+            activity: self.activity.snapshot(Utc::now()),
+        }
+    }
+
+    fn control_status(&mut self, connected: bool) -> Status {
+        Status {
+            paused: self.paused,
+            paused_until: self.paused_until,
+            connected,
+            activity: self.activity.snapshot(Utc::now()),
+        }
+    }
+}
+
+/// Pauses tracking, a timed pause ends with the `timer`.
+async fn pause_tracking(
+    recorder: &mut Recorder,
+    duration: Option<Duration>,
+    timer: Pin<&mut Sleep>,
+) -> Result<()> {
+    if !recorder.paused {
+        info!("Pausing tracking.");
+        recorder.record(Event::Paused).await?;
+        recorder.paused = true;
+    }
+
+    // Pauses too long to represent are left untimed.
+    let deadline = duration.and_then(|duration| {
+        let until = Instant::now().checked_add(duration)?;
+        let paused_until = Utc::now()
+            .checked_add_signed(TimeDelta::from_std(duration).ok()?)?;
+
+        Some((until, paused_until))
+    });
+
+    recorder.paused_until = deadline.map(|(until, paused_until)| {
+        timer.reset(until);
+        paused_until
+    });
+
+    Ok(())
+}
+
+async fn reload_privacy(config_file: &PathBuf) -> Result<PrivacyFilter> {
+    let cfg = load_config(config_file).await?;
+
+    PrivacyFilter::new(&cfg.sway.privacy)
+        .context("Failed to load the privacy rules")
+}
+
+async fn resume_tracking(
+    sway: &mut Option<SwayConnection>,
+    privacy: &PrivacyFilter,
+    recorder: &mut Recorder,
+) -> Result<()> {
+    if !recorder.paused {
+        return Ok(());
+    }
+
+    info!("Resuming tracking.");
+    recorder.paused = false;
+    recorder.paused_until = None;
+    recorder.record(Event::Resumed).await?;
+
+    // The focus has likely changed during the pause.
+    if let Some(connection) = sway {
+        write_current_focus(&mut connection.client, privacy, recorder).await?;
+    }
+
+    Ok(())
+}
+
+fn open_viewer() {
+    match tokio::process::Command::new(matiane_core::NAME).spawn() {
+        Ok(_) => debug!("Opened {}.", matiane_core::NAME),
+        Err(err) => warn!("Could not open {}: {}", matiane_core::NAME, err),
+    }
+}
+
+async fn write_current_focus(
+    client: &mut SwayClient,
+    privacy: &PrivacyFilter,
+    recorder: &mut Recorder,
+) -> Result<()> {
+    match current_focus(client).await {
+        Ok(Some(focused)) => {
+            let event = focus_event(privacy, focused);
+            recorder.record(event).await?;
+        }
+        Ok(None) => debug!("No window has the focus."),
+        Err(err) => warn!("Could not get the focused window: {}", err),
+    }
+
+    Ok(())
+}
+
 fn timed_event(event: Event) -> TimedEvent {
     TimedEvent {
         timestamp: Utc::now(),
@@ -225,9 +685,16 @@ fn timed_event(event: Event) -> TimedEvent {
 }
 
 fn run_swayidle(
-    idletimer: u32,
+    idletimer: Option<u32>,
+    sleep_hooks: bool,
     token: CancellationToken,
-) -> Result<RunningHandle> {
+) -> Result<Option<RunningHandle>> {
+    if idletimer.is_none() && !sleep_hooks {
+        debug!("Nothing is left for swayidle.");
+        return Ok(None);
+    }
+
+    debug!("Running swayidle...");
     let mut sway_idle = swayidle::SwayIdle::new();
     let pid = std::process::id();
 
@@ -241,15 +708,20 @@ fn run_swayidle(
     let after_sleep =
         swayidle::AfterResume::new(format!("kill -{} {}", sigusr2, pid));
 
-    let on_idle = swayidle::Timeout::new_with_resume(
-        format!("kill -{} {}", idlesignal, pid),
-        idletimer,
-        format!("kill -{} {}", resumesignal, pid),
-    );
+    if sleep_hooks {
+        sway_idle.add_command(before_sleep);
+        sway_idle.add_command(after_sleep);
+    }
+
+    if let Some(idletimer) = idletimer {
+        let on_idle = swayidle::Timeout::new_with_resume(
+            format!("kill -{} {}", idlesignal, pid),
+            idletimer,
+            format!("kill -{} {}", resumesignal, pid),
+        );
 
-    sway_idle.add_command(before_sleep);
-    sway_idle.add_command(after_sleep);
-    sway_idle.add_command(on_idle);
+        sway_idle.add_command(on_idle);
+    }
 
-    sway_idle.spawn(token)
+    sway_idle.spawn(token).map(Some)
 }
@@ -0,0 +1,311 @@
+use matiane_core::events::Focused;
+use regex::Regex;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use thiserror::Error;
+
+pub const DEFAULT_PLACEHOLDER: &str = "Redacted";
+
+/// Hex characters of the title digest that are kept.
+const HASH_LENGTH: usize = 16;
+
+#[derive(Debug, Error)]
+pub enum PrivacyError {
+    #[error("Privacy rule #{index} has an invalid regex: {source}")]
+    InvalidRegex {
+        index: usize,
+        #[source]
+        source: regex::Error,
+    },
+
+    #[error("Privacy rule #{0} does not match on anything.")]
+    EmptyRule(usize),
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrivacyAction {
+    /// Nothing about the window is written, its time is not attributed.
+    Drop,
+    /// The title is replaced with the placeholder.
+    Redact,
+    /// The title is replaced with its digest, equal titles stay equal.
+    Hash,
+}
+
+/// Single rule from the config, every condition that is set must match.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PrivacyRuleConfig {
+    pub action: PrivacyAction,
+    /// Regex searched in the application id.
+    pub app_id: Option<String>,
+    /// Regex searched in the window title.
+    pub title: Option<String>,
+    /// Title of the redacted windows.
+    pub placeholder: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    action: PrivacyAction,
+    app_id: Option<Regex>,
+    title: Option<Regex>,
+    placeholder: String,
+}
+
+impl Rule {
+    fn new(
+        index: usize,
+        config: &PrivacyRuleConfig,
+    ) -> Result<Self, PrivacyError> {
+        if config.app_id.is_none() && config.title.is_none() {
+            return Err(PrivacyError::EmptyRule(index));
+        }
+
+        let regex =
+            |pattern: &Option<String>| {
+                pattern.as_deref().map(Regex::new).transpose().map_err(
+                    |source| PrivacyError::InvalidRegex { index, source },
+                )
+            };
+
+        Ok(Rule {
+            action: config.action,
+            app_id: regex(&config.app_id)?,
+            title: regex(&config.title)?,
+            placeholder: config
+                .placeholder
+                .clone()
+                .unwrap_or_else(|| DEFAULT_PLACEHOLDER.into()),
+        })
+    }
+
+    fn matches(&self, focused: &Focused) -> bool {
+        if let Some(app_id) = &self.app_id
+            && !app_id.is_match(&focused.id)
+        {
+            return false;
+        }
+
+        if let Some(title) = &self.title
+            && !title.is_match(&focused.title)
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Compiled privacy rules, applied to the focus events before they are
+/// written.
+#[derive(Debug, Clone, Default)]
+pub struct PrivacyFilter {
+    rules: Vec<Rule>,
+}
+
+impl PrivacyFilter {
+    pub fn new(config: &[PrivacyRuleConfig]) -> Result<Self, PrivacyError> {
+        let rules = config
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| Rule::new(index + 1, rule))
+            .collect::<Result<_, _>>()?;
+
+        Ok(PrivacyFilter { rules })
+    }
+
+    /// The event to write, `None` when it has to be dropped. The first
+    /// matching rule wins.
+    pub fn apply(&self, mut focused: Focused) -> Option<Focused> {
+        let Some(rule) = self.rules.iter().find(|rule| rule.matches(&focused))
+        else {
+            return Some(focused);
+        };
+
+        match rule.action {
+            PrivacyAction::Drop => return None,
+            PrivacyAction::Redact => focused.title = rule.placeholder.clone(),
+            PrivacyAction::Hash => focused.title = hash_title(&focused.title),
+        }
+
+        Some(focused)
+    }
+}
+
+fn hash_title(title: &str) -> String {
+    let digest = Sha256::digest(title.as_bytes());
+    let mut hashed = String::from("sha256:");
+
+    for byte in digest.iter().take(HASH_LENGTH / 2) {
+        let _ = write!(hashed, "{:02x}", byte);
+    }
+
+    hashed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[derive(Deserialize)]
+    struct Config {
+        privacy: Vec<PrivacyRuleConfig>,
+    }
+
+    const CONFIG: &str = r#"
+        [[privacy]]
+        action = "drop"
+        app-id = "^org\\.keepassxc\\."
+
+        [[privacy]]
+        action = "redact"
+        app-id = "^firefox$"
+        title = "(?i)private browsing"
+        placeholder = "Private window"
+
+        [[privacy]]
+        action = "hash"
+        app-id = "^(signal|telegram)"
+
+        [[privacy]]
+        action = "redact"
+        title = "(?i)bank"
+    "#;
+
+    fn filter() -> Result<PrivacyFilter> {
+        let config: Config = toml::from_str(CONFIG)?;
+        Ok(PrivacyFilter::new(&config.privacy)?)
+    }
+
+    fn focused(id: &str, title: &str) -> Focused {
+        Focused {
+            title: title.into(),
+            id: id.into(),
+            pid: 1,
+            workspace: Some("1".into()),
+            ..Default::default()
+        }
+    }
+
+    fn title(filter: &PrivacyFilter, id: &str, title: &str) -> Option<String> {
+        filter
+            .apply(focused(id, title))
+            .map(|focused| focused.title)
+    }
+
+    #[test]
+    fn privacy_drop() -> Result<()> {
+        let filter = filter()?;
+
+        assert_eq!(
+            filter.apply(focused("org.keepassxc.KeePassXC", "Passwords")),
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn privacy_redact() -> Result<()> {
+        let filter = filter()?;
+
+        let redacted =
+            filter.apply(focused("firefox", "News — Private Browsing"));
+        assert_eq!(
+            redacted,
+            Some(Focused {
+                title: "Private window".into(),
+                ..focused("firefox", "")
+            })
+        );
+
+        assert_eq!(
+            title(&filter, "chromium", "My Bank - Login"),
+            Some(DEFAULT_PLACEHOLDER.into())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn privacy_hash() -> Result<()> {
+        let filter = filter()?;
+
+        let hashed = title(&filter, "signal", "Chat with Alice").unwrap();
+        assert!(hashed.starts_with("sha256:"));
+        assert_eq!(hashed.len(), "sha256:".len() + HASH_LENGTH);
+        assert!(!hashed.contains("Alice"));
+
+        assert_eq!(
+            title(&filter, "telegram-desktop", "Chat with Alice"),
+            Some(hashed.clone())
+        );
+        assert_ne!(title(&filter, "signal", "Chat with Bob"), Some(hashed));
+
+        Ok(())
+    }
+
+    #[test]
+    fn privacy_first_match_wins() -> Result<()> {
+        let filter = filter()?;
+
+        // Hashed before the bank rule is reached.
+        let hashed = title(&filter, "signal", "bank").unwrap();
+        assert!(hashed.starts_with("sha256:"));
+
+        assert_eq!(
+            title(&filter, "firefox", "News - Mozilla Firefox"),
+            Some("News - Mozilla Firefox".into())
+        );
+        assert!(PrivacyFilter::default().apply(focused("a", "b")).is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn privacy_invalid_rules() -> Result<()> {
+        let config: Config = toml::from_str(
+            r#"
+            [[privacy]]
+            action = "drop"
+            app-id = "firefox"
+
+            [[privacy]]
+            action = "hash"
+            title = "(unclosed"
+            "#,
+        )?;
+
+        assert!(matches!(
+            PrivacyFilter::new(&config.privacy),
+            Err(PrivacyError::InvalidRegex { index: 2, .. })
+        ));
+
+        let config: Config = toml::from_str(
+            r#"
+            [[privacy]]
+            action = "drop"
+            "#,
+        )?;
+
+        assert!(matches!(
+            PrivacyFilter::new(&config.privacy),
+            Err(PrivacyError::EmptyRule(1))
+        ));
+
+        let unknown = toml::from_str::<Config>(
+            r#"
+            [[privacy]]
+            action = "remove"
+            app-id = "firefox"
+            "#,
+        );
+        assert!(unknown.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+use crate::sway::client::{ClientError, SwayClient};
+use crate::sway::command::EventType;
+use crate::sway::connection::{SubscribeError, subscribe};
+use crate::sway::reply::Event as SwayEvent;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use log::{debug, info, warn};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+/// Events the daemon reacts to.
+pub const EVENTS: [EventType; 2] = [EventType::Window, EventType::Shutdown];
+
+pub const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Error)]
+pub enum ConnectError {
+    #[error("Client error: {0}")]
+    Client(#[from] ClientError),
+    #[error("Subscribe error: {0}")]
+    Subscribe(#[from] SubscribeError),
+    #[error("No sway socket was found.")]
+    NoSocket,
+}
+
+/// Request client and the event subscription on the same sway socket.
+pub struct SwayConnection {
+    pub path: PathBuf,
+    pub client: SwayClient,
+    pub events: BoxStream<'static, Result<SwayEvent, SubscribeError>>,
+}
+
+impl SwayConnection {
+    pub async fn open(path: &Path) -> Result<Self, ConnectError> {
+        let mut client = SwayClient::connect(path).await?;
+        let version = client.get_version().await?;
+        info!(
+            "Connected to sway {} on {:?}.",
+            version.human_readable, path
+        );
+
+        let events = subscribe(&path.to_path_buf(), &EVENTS).await?.boxed();
+
+        Ok(SwayConnection {
+            path: path.to_path_buf(),
+            client,
+            events,
+        })
+    }
+
+    /// Tries `preferred` first and then the other sway sockets in the
+    /// runtime dir, a restarted sway listens on a new one.
+    pub async fn reconnect(
+        preferred: &Path,
+        runtime_dir: &Path,
+    ) -> Result<Self, ConnectError> {
+        let mut candidates = vec![preferred.to_path_buf()];
+        candidates.extend(
+            find_sockets(runtime_dir)
+                .into_iter()
+                .filter(|path| path != preferred),
+        );
+
+        let mut last_error = ConnectError::NoSocket;
+
+        for path in candidates {
+            debug!("Trying sway socket {:?}...", path);
+
+            match SwayConnection::open(&path).await {
+                Ok(connection) => return Ok(connection),
+                Err(err) => {
+                    debug!("Could not connect to {:?}: {}", path, err);
+                    last_error = err;
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+}
+
+/// Sway IPC sockets in the runtime dir, the most recent first.
+pub fn find_sockets(runtime_dir: &Path) -> Vec<PathBuf> {
+    let entries = match std::fs::read_dir(runtime_dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            warn!("Could not read {:?}: {}", runtime_dir, err);
+            return vec![];
+        }
+    };
+
+    let mut sockets: Vec<(SystemTime, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+
+            name.starts_with("sway-ipc.") && name.ends_with(".sock")
+        })
+        .map(|entry| {
+            let modified = entry
+                .metadata()
+                .and_then(|meta| meta.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+
+            (modified, entry.path())
+        })
+        .collect();
+
+    sockets.sort_by(|a, b| b.cmp(a));
+    sockets.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Doubling delay between the reconnect attempts.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Backoff {
+            initial,
+            max,
+            next: initial,
+        }
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(self.max);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.next = self.initial;
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff::new(INITIAL_BACKOFF, MAX_BACKOFF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use std::fs::{File, FileTimes};
+    use tempfile::Builder;
+
+    #[test]
+    fn backoff_doubles_until_max() {
+        let mut backoff =
+            Backoff::new(Duration::from_secs(1), Duration::from_secs(5));
+
+        let delays: Vec<u64> =
+            (0..5).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn find_sockets_newest_first() -> Result<()> {
+        let dir = Builder::new()
+            .prefix("sway-matiane-find-sockets")
+            .rand_bytes(10)
+            .tempdir()?;
+
+        let touch = |name: &str, secs: u64| -> Result<()> {
+            let file = File::create(dir.path().join(name))?;
+            let time = SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+            file.set_times(FileTimes::new().set_modified(time))?;
+            Ok(())
+        };
+
+        touch("sway-ipc.1000.100.sock", 100)?;
+        touch("sway-ipc.1000.300.sock", 300)?;
+        touch("sway-ipc.1000.200.sock", 200)?;
+        touch("wayland-1", 400)?;
+        touch("sway-ipc.1000.500.sock.lock", 500)?;
+
+        let sockets = find_sockets(dir.path());
+
+        assert_eq!(
+            sockets,
+            vec![
+                dir.path().join("sway-ipc.1000.300.sock"),
+                dir.path().join("sway-ipc.1000.200.sock"),
+                dir.path().join("sway-ipc.1000.100.sock"),
+            ]
+        );
+
+        assert!(find_sockets(&dir.path().join("missing")).is_empty());
+
+        Ok(())
+    }
+}
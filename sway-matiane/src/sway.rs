@@ -3,8 +3,10 @@ pub const MAGIC_LEN: usize = 6;
 
 pub const EVENT_FLAG: u32 = 0x80000000;
 
+pub mod client;
 pub mod codec;
 pub mod command;
 pub mod connection;
 pub mod packet;
 pub mod reply;
+pub mod tree;
@@ -0,0 +1,168 @@
+// https://man.archlinux.org/man/sway-ipc.7#MESSAGES_AND_REPLIES
+
+use super::codec::{SwayPacketCodec, SwayPacketCodecError};
+use super::command::CommandType;
+use super::packet::SwayPacketRaw;
+use super::reply::{
+    BarConfig, BindingState, CommandError, CommandOutcome, Config, Input, Node,
+    Output, Seat, Success, Version, Workspace,
+};
+use futures::{SinkExt, StreamExt};
+use log::{debug, trace};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use thiserror::Error;
+use tokio::net::UnixStream;
+use tokio_util::codec::Framed;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Terrible packet `{0}`.")]
+    TerriblePacket(#[from] SwayPacketCodecError),
+    #[error("Stream closed")]
+    Closed,
+    #[error("Incorrect response type `{got}`, expected `{expected:?}`.")]
+    IncorrectResponseType { expected: CommandType, got: u32 },
+    #[error("Bad payload: {0}")]
+    BadPayload(#[from] serde_json::Error),
+}
+
+/// Request/response connection to sway. Replies arrive in the order of the
+/// requests, so a single client handles one request at a time.
+#[derive(Debug)]
+pub struct SwayClient {
+    framer: Framed<UnixStream, SwayPacketCodec>,
+}
+
+impl SwayClient {
+    pub async fn connect(path: impl AsRef<Path>) -> Result<Self, ClientError> {
+        let path = path.as_ref();
+
+        debug!("Connecting to {:?}...", path);
+        let socket = UnixStream::connect(path).await?;
+        debug!("Connected to {:?}.", path);
+
+        Ok(SwayClient {
+            framer: Framed::new(socket, SwayPacketCodec),
+        })
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &mut self,
+        command: CommandType,
+        payload: &str,
+    ) -> Result<T, ClientError> {
+        trace!("Sending {:?}.", command);
+
+        self.framer
+            .send(SwayPacketRaw {
+                packet_type: command as u32,
+                payload: payload.to_owned().into(),
+            })
+            .await?;
+
+        let response =
+            self.framer.next().await.ok_or(ClientError::Closed)??;
+
+        if response.packet_type != command as u32 {
+            return Err(ClientError::IncorrectResponseType {
+                expected: command,
+                got: response.packet_type,
+            });
+        }
+
+        Ok(serde_json::from_slice(&response.payload)?)
+    }
+
+    /// Runs the sway commands, one outcome per command.
+    pub async fn run_command(
+        &mut self,
+        command: &str,
+    ) -> Result<Vec<Result<(), CommandError>>, ClientError> {
+        let outcomes: Vec<CommandOutcome> =
+            self.request(CommandType::RunCommand, command).await?;
+
+        Ok(outcomes
+            .into_iter()
+            .map(|outcome| match (outcome.success, outcome.error) {
+                (false, Some(error)) => Err(error),
+                _ => Ok(()),
+            })
+            .collect())
+    }
+
+    pub async fn get_workspaces(
+        &mut self,
+    ) -> Result<Vec<Workspace>, ClientError> {
+        self.request(CommandType::GetWorkspaces, "").await
+    }
+
+    pub async fn get_outputs(&mut self) -> Result<Vec<Output>, ClientError> {
+        self.request(CommandType::GetOutputs, "").await
+    }
+
+    pub async fn get_tree(&mut self) -> Result<Node, ClientError> {
+        self.request(CommandType::GetTree, "").await
+    }
+
+    pub async fn get_marks(&mut self) -> Result<Vec<String>, ClientError> {
+        self.request(CommandType::GetMarks, "").await
+    }
+
+    pub async fn get_bar_ids(&mut self) -> Result<Vec<String>, ClientError> {
+        self.request(CommandType::GetBarConfig, "").await
+    }
+
+    pub async fn get_bar_config(
+        &mut self,
+        id: &str,
+    ) -> Result<BarConfig, ClientError> {
+        self.request(CommandType::GetBarConfig, id).await
+    }
+
+    pub async fn get_version(&mut self) -> Result<Version, ClientError> {
+        self.request(CommandType::GetVersion, "").await
+    }
+
+    pub async fn get_binding_modes(
+        &mut self,
+    ) -> Result<Vec<String>, ClientError> {
+        self.request(CommandType::GetBindingModes, "").await
+    }
+
+    pub async fn get_config(&mut self) -> Result<Config, ClientError> {
+        self.request(CommandType::GetConfig, "").await
+    }
+
+    /// Sends a tick event with the payload to the subscribed clients.
+    pub async fn send_tick(
+        &mut self,
+        payload: &str,
+    ) -> Result<bool, ClientError> {
+        let reply: Success =
+            self.request(CommandType::SendTick, payload).await?;
+        Ok(reply.success)
+    }
+
+    /// Sway always replies with a failure, kept for i3 compatibility.
+    pub async fn sync(&mut self) -> Result<bool, ClientError> {
+        let reply: Success = self.request(CommandType::Sync, "").await?;
+        Ok(reply.success)
+    }
+
+    pub async fn get_binding_state(
+        &mut self,
+    ) -> Result<BindingState, ClientError> {
+        self.request(CommandType::GetBindingState, "").await
+    }
+
+    pub async fn get_inputs(&mut self) -> Result<Vec<Input>, ClientError> {
+        self.request(CommandType::GetInputs, "").await
+    }
+
+    pub async fn get_seats(&mut self) -> Result<Vec<Seat>, ClientError> {
+        self.request(CommandType::GetSeats, "").await
+    }
+}
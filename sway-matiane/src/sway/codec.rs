@@ -133,18 +133,18 @@ mod tests {
     #[tokio::test]
     async fn decode_normal() {
         let payload: &[u8] = b"{}";
-        let payload_type = 4;
+        let payload_type: u32 = 4;
         let payload2: &[u8] = b"something_else";
-        let payload2_type = 1;
+        let payload2_type: u32 = 1;
 
         let mock = Builder::new()
             .read(b"i3-ipc")
             .read(&(payload.len() as u32).to_ne_bytes())
-            .read(&(payload_type as u32).to_ne_bytes())
+            .read(&payload_type.to_ne_bytes())
             .read(payload)
             .read(b"i3-ipc")
             .read(&(payload2.len() as u32).to_ne_bytes())
-            .read(&(payload2_type as u32).to_ne_bytes())
+            .read(&payload2_type.to_ne_bytes())
             .read(payload2)
             .build();
 
@@ -168,12 +168,12 @@ mod tests {
     #[tokio::test]
     async fn decode_cancel_continue() {
         let payload: &[u8] = b"{}";
-        let payload_type = 1;
+        let payload_type: u32 = 1;
 
         let mock = Builder::new()
             .read(b"i3-ipc")
             .read(&(payload.len() as u32).to_ne_bytes())
-            .read(&(payload_type as u32).to_ne_bytes())
+            .read(&payload_type.to_ne_bytes())
             .wait(Duration::from_millis(50))
             .read(payload)
             .build();
@@ -202,18 +202,18 @@ mod tests {
     #[tokio::test]
     async fn encode() {
         let payload: &[u8] = b"{}";
-        let payload_type = 10;
+        let payload_type: u32 = 10;
         let payload2: &[u8] = b"something_else";
-        let payload2_type = 101;
+        let payload2_type: u32 = 101;
 
         let mock = Builder::new()
             .write(b"i3-ipc")
             .write(&(payload.len() as u32).to_ne_bytes())
-            .write(&(payload_type as u32).to_ne_bytes())
+            .write(&payload_type.to_ne_bytes())
             .write(payload)
             .write(b"i3-ipc")
             .write(&(payload2.len() as u32).to_ne_bytes())
-            .write(&(payload2_type as u32).to_ne_bytes())
+            .write(&payload2_type.to_ne_bytes())
             .write(payload2)
             .build();
 
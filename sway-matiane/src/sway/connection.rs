@@ -21,8 +21,6 @@ pub enum SubscribeError {
     SubscribeFailed(super::reply::CommandError),
     #[error("Subscribed response is not an event: `{0}`.")]
     NotAnEvent(u32),
-    #[error("Unsupported event: `{0}`.")]
-    UnsupportedEvent(u32),
     #[error("Terrible packet `{0}`.")]
     TerriblePacket(#[from] SwayPacketCodecError),
     #[error("Stream closed")]
@@ -44,18 +42,41 @@ impl TryFrom<SwayPacketRaw> for Event {
         let event_type =
             EventType::try_from(packet.packet_type ^ super::EVENT_FLAG)?;
 
-        match event_type {
+        let payload = &packet.payload;
+
+        Ok(match event_type {
+            EventType::Workspace => {
+                Event::Workspace(serde_json::from_slice(payload)?)
+            }
+            EventType::Output => {
+                Event::Output(serde_json::from_slice(payload)?)
+            }
+            EventType::Mode => Event::Mode(serde_json::from_slice(payload)?),
             EventType::Window => {
-                Ok(Event::Window(serde_json::from_slice(&packet.payload)?))
+                Event::Window(serde_json::from_slice(payload)?)
             }
-            _ => Err(SubscribeError::UnsupportedEvent(event_type as u32)),
-        }
+            EventType::BarConfigUpdate => {
+                Event::BarConfigUpdate(serde_json::from_slice(payload)?)
+            }
+            EventType::Binding => {
+                Event::Binding(serde_json::from_slice(payload)?)
+            }
+            EventType::Shutdown => {
+                Event::Shutdown(serde_json::from_slice(payload)?)
+            }
+            EventType::Tick => Event::Tick(serde_json::from_slice(payload)?),
+            EventType::BarStateUpdate => {
+                Event::BarStateUpdate(serde_json::from_slice(payload)?)
+            }
+            EventType::Input => Event::Input(serde_json::from_slice(payload)?),
+        })
     }
 }
 
-fn subscribe_packet(event: EventType) -> Result<SwayPacketRaw, SubscribeError> {
-    let events = [event];
-    let encoded = serde_json::ser::to_string(&events)?;
+fn subscribe_packet(
+    events: &[EventType],
+) -> Result<SwayPacketRaw, SubscribeError> {
+    let encoded = serde_json::ser::to_string(events)?;
 
     Ok(SwayPacketRaw {
         packet_type: CommandType::Subscribe as u32,
@@ -65,9 +86,9 @@ fn subscribe_packet(event: EventType) -> Result<SwayPacketRaw, SubscribeError> {
 
 pub async fn subscribe(
     path: &PathBuf,
-    event: EventType,
+    events: &[EventType],
 ) -> Result<
-    impl Debug + StreamExt<Item = Result<Event, SubscribeError>>,
+    impl Debug + StreamExt<Item = Result<Event, SubscribeError>> + use<>,
     SubscribeError,
 > {
     debug!("Connecting to {:?}...", path);
@@ -76,8 +97,8 @@ pub async fn subscribe(
 
     let mut framer = Framed::new(socket, SwayPacketCodec);
 
-    debug!("Subscribing to events: {:?}...", event);
-    let packet = subscribe_packet(event)?;
+    debug!("Subscribing to events: {:?}...", events);
+    let packet = subscribe_packet(events)?;
     framer.send(packet).await?;
 
     let response = framer.next().await.ok_or(SubscribeError::Closed)??;
@@ -93,7 +114,7 @@ pub async fn subscribe(
         return Err(SubscribeError::SubscribeFailed(outcome.error.unwrap()));
     }
 
-    debug!("Subscribed to events: {:?}.", event);
+    debug!("Subscribed to events: {:?}.", events);
     Ok(framer.map(|res| Event::try_from(res?)))
 }
 
@@ -103,9 +124,19 @@ mod tests {
 
     #[test]
     fn subscribe_packet_test() -> anyhow::Result<()> {
-        let packet = EventType::Window;
-
-        subscribe_packet(packet)?;
+        let packet = subscribe_packet(&[EventType::Window])?;
+        assert_eq!(packet.packet_type, CommandType::Subscribe as u32);
+        assert_eq!(&packet.payload[..], br#"["window"]"#);
+
+        let packet = subscribe_packet(&[
+            EventType::Workspace,
+            EventType::BarConfigUpdate,
+            EventType::BarStateUpdate,
+        ])?;
+        assert_eq!(
+            &packet.payload[..],
+            br#"["workspace","barconfig_update","bar_state_update"]"#
+        );
 
         Ok(())
     }
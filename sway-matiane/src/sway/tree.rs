@@ -0,0 +1,276 @@
+use super::reply::{Node, NodeType};
+
+/// Where a container sits in the layout tree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Location {
+    pub workspace: Option<String>,
+    /// Only set when the workspace name starts with a number.
+    pub workspace_num: Option<i32>,
+    pub output: Option<String>,
+}
+
+/// Finds the workspace and the output of the container `id`.
+pub fn locate(root: &Node, id: i64) -> Option<Location> {
+    let mut path = vec![];
+
+    if !find_path(root, id, &mut path) {
+        return None;
+    }
+
+    let mut location = Location::default();
+
+    for node in path {
+        match node.node_type {
+            NodeType::Output => location.output = node.name.clone(),
+            NodeType::Workspace => {
+                location.workspace = node.name.clone();
+                location.workspace_num = node.num.filter(|num| *num >= 0);
+            }
+            _ => {}
+        }
+    }
+
+    Some(location)
+}
+
+/// The focused window, `None` when the focus is on an empty workspace.
+pub fn find_focused(root: &Node) -> Option<&Node> {
+    if root.focused
+        && matches!(root.node_type, NodeType::Con | NodeType::FloatingCon)
+    {
+        return Some(root);
+    }
+
+    root.nodes
+        .iter()
+        .chain(&root.floating_nodes)
+        .find_map(find_focused)
+}
+
+/// A view that keeps the user present without any input: it inhibits idle
+/// or is visible in fullscreen.
+pub fn find_presence(root: &Node) -> Option<&Node> {
+    let inhibits = root.inhibit_idle == Some(true);
+    let fullscreen = root.fullscreen_mode.is_some_and(|mode| mode > 0)
+        && root.visible == Some(true);
+
+    if inhibits || fullscreen {
+        return Some(root);
+    }
+
+    root.nodes
+        .iter()
+        .chain(&root.floating_nodes)
+        .find_map(find_presence)
+}
+
+fn find_path<'a>(node: &'a Node, id: i64, path: &mut Vec<&'a Node>) -> bool {
+    path.push(node);
+
+    if node.id == id {
+        return true;
+    }
+
+    for child in node.nodes.iter().chain(&node.floating_nodes) {
+        if find_path(child, id, path) {
+            return true;
+        }
+    }
+
+    path.pop();
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use serde_json::{Value, json};
+
+    fn node(
+        id: i64,
+        node_type: &str,
+        name: &str,
+        num: Option<i32>,
+        nodes: Vec<Value>,
+        floating_nodes: Vec<Value>,
+    ) -> Value {
+        let rect = json!({ "x": 0, "y": 0, "width": 10, "height": 10 });
+
+        json!({
+            "id": id,
+            "name": name,
+            "type": node_type,
+            "num": num,
+            "border": "none",
+            "current_border_width": 0,
+            "layout": "splith",
+            "rect": rect,
+            "window_rect": rect,
+            "deco_rect": rect,
+            "geometry": rect,
+            "urgent": false,
+            "focused": false,
+            "focus": [],
+            "sticky": false,
+            "nodes": nodes,
+            "floating_nodes": floating_nodes,
+        })
+    }
+
+    fn tree() -> Result<Node> {
+        let tree = node(
+            1,
+            "root",
+            "root",
+            None,
+            vec![
+                node(
+                    2,
+                    "output",
+                    "eDP-1",
+                    None,
+                    vec![
+                        node(
+                            3,
+                            "workspace",
+                            "2: mail",
+                            Some(2),
+                            vec![node(10, "con", "mutt", None, vec![], vec![])],
+                            vec![node(
+                                11,
+                                "floating_con",
+                                "popup",
+                                None,
+                                vec![],
+                                vec![],
+                            )],
+                        ),
+                        node(
+                            4,
+                            "workspace",
+                            "chat",
+                            Some(-1),
+                            vec![node(
+                                5,
+                                "con",
+                                "",
+                                None,
+                                vec![node(
+                                    12,
+                                    "con",
+                                    "irc",
+                                    None,
+                                    vec![],
+                                    vec![],
+                                )],
+                                vec![],
+                            )],
+                            vec![],
+                        ),
+                    ],
+                    vec![],
+                ),
+                node(
+                    6,
+                    "output",
+                    "HDMI-A-1",
+                    None,
+                    vec![node(
+                        7,
+                        "workspace",
+                        "5: client-x",
+                        Some(5),
+                        vec![node(13, "con", "vim", None, vec![], vec![])],
+                        vec![],
+                    )],
+                    vec![],
+                ),
+            ],
+            vec![],
+        );
+
+        Ok(serde_json::from_value(tree)?)
+    }
+
+    fn location(workspace: &str, num: Option<i32>, output: &str) -> Location {
+        Location {
+            workspace: Some(workspace.into()),
+            workspace_num: num,
+            output: Some(output.into()),
+        }
+    }
+
+    #[test]
+    fn locate_containers() -> Result<()> {
+        let tree = tree()?;
+
+        assert_eq!(
+            locate(&tree, 10),
+            Some(location("2: mail", Some(2), "eDP-1"))
+        );
+        assert_eq!(
+            locate(&tree, 11),
+            Some(location("2: mail", Some(2), "eDP-1"))
+        );
+        assert_eq!(locate(&tree, 12), Some(location("chat", None, "eDP-1")));
+        assert_eq!(
+            locate(&tree, 13),
+            Some(location("5: client-x", Some(5), "HDMI-A-1"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_focused_window() -> Result<()> {
+        let mut tree = tree()?;
+        assert_eq!(find_focused(&tree), None);
+
+        // Empty workspace.
+        tree.nodes[0].nodes[1].focused = true;
+        assert_eq!(find_focused(&tree), None);
+
+        tree.nodes[0].nodes[1].focused = false;
+        tree.nodes[0].nodes[0].floating_nodes[0].focused = true;
+        assert_eq!(find_focused(&tree).map(|node| node.id), Some(11));
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_presence_view() -> Result<()> {
+        let mut tree = tree()?;
+        assert_eq!(find_presence(&tree), None);
+
+        // Fullscreen on a hidden workspace.
+        tree.nodes[1].nodes[0].nodes[0].fullscreen_mode = Some(1);
+        tree.nodes[1].nodes[0].nodes[0].visible = Some(false);
+        assert_eq!(find_presence(&tree), None);
+
+        tree.nodes[1].nodes[0].nodes[0].visible = Some(true);
+        assert_eq!(find_presence(&tree).map(|node| node.id), Some(13));
+
+        tree.nodes[1].nodes[0].nodes[0].fullscreen_mode = Some(0);
+        tree.nodes[0].nodes[1].nodes[0].nodes[0].inhibit_idle = Some(true);
+        assert_eq!(find_presence(&tree).map(|node| node.id), Some(12));
+
+        Ok(())
+    }
+
+    #[test]
+    fn locate_outside_of_workspace() -> Result<()> {
+        let tree = tree()?;
+
+        assert_eq!(locate(&tree, 99), None);
+        assert_eq!(
+            locate(&tree, 2),
+            Some(Location {
+                output: Some("eDP-1".into()),
+                ..Default::default()
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -1,20 +1,73 @@
+pub mod menu;
+
 use zbus::{
     Connection, connection,
     fdo::DBusProxy,
     interface,
+    object_server::SignalEmitter,
     zvariant::{ObjectPath, Type, Value},
 };
 
 use futures::StreamExt;
 use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::watch;
 use tokio::task::{JoinHandle, spawn};
 use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
 
-use log::debug;
+use crate::activity::Snapshot;
+use chrono::{DateTime, Utc};
+use log::{debug, warn};
+use menu::{MENU_PATH, Menu};
 use thiserror::Error;
 
-pub struct Tray;
+const TRAY_PATH: &str = "/StatusNotifierItem";
+const PAUSED_ICON: &str = "media-playback-pause";
+
+/// Requests from the tray menu to the daemon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayCommand {
+    /// Pause tracking, for a while or until resumed.
+    Pause(Option<Duration>),
+    Resume,
+    /// Open the matiane viewer.
+    Open,
+    Quit,
+}
+
+/// Daemon state shown by the tray.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrayStatus {
+    pub paused: bool,
+    pub activity: Snapshot,
+}
+
+impl TrayStatus {
+    /// Tooltip title and description, durations are counted up to `now`.
+    pub fn tool_tip(&self, now: DateTime<Utc>) -> (String, String) {
+        let title = match &self.activity.focused {
+            Some(focused) => focused.id.clone(),
+            None => matiane_core::NAME.into(),
+        };
+
+        let lines = self.activity.describe(self.paused, now);
+        (title, lines.join("\n"))
+    }
+}
+
+pub struct Tray {
+    status: TrayStatus,
+    commands: UnboundedSender<TrayCommand>,
+}
+
+fn logo() -> Icon {
+    Icon {
+        width: ICON_WIDTH,
+        height: ICON_HEIGHT,
+        data: ICON_DATA.to_vec(),
+    }
+}
 
 const ICON_WIDTH: i32 = 256;
 const ICON_HEIGHT: i32 = 256;
@@ -54,7 +107,10 @@ impl Tray {
 
     #[zbus(property)]
     async fn status(&self) -> String {
-        "Active".into()
+        match self.status.paused {
+            true => "NeedsAttention".into(),
+            false => "Active".into(),
+        }
     }
 
     #[zbus(property)]
@@ -74,7 +130,7 @@ impl Tray {
 
     #[zbus(property)]
     async fn menu(&self) -> ObjectPath<'_> {
-        ObjectPath::from_static_str_unchecked("/")
+        ObjectPath::from_static_str_unchecked(MENU_PATH)
     }
 
     #[zbus(property)]
@@ -84,18 +140,15 @@ impl Tray {
 
     #[zbus(property)]
     async fn icon_pixmap(&self) -> Vec<Icon> {
-        let icon = Icon {
-            width: ICON_WIDTH,
-            height: ICON_HEIGHT,
-            data: ICON_DATA.to_vec(),
-        };
-
-        vec![icon]
+        vec![logo()]
     }
 
     #[zbus(property)]
     async fn overlay_icon_name(&self) -> String {
-        "".into()
+        match self.status.paused {
+            true => PAUSED_ICON.into(),
+            false => "".into(),
+        }
     }
 
     #[zbus(property)]
@@ -112,24 +165,81 @@ impl Tray {
         "".into()
     }
 
+    /// The attention icon is shown while paused, with the pause overlay.
     #[zbus(property)]
     async fn attention_icon_pixmap(&self) -> Vec<Icon> {
-        vec![Icon {
-            width: 0,
-            height: 0,
-            data: vec![],
-        }]
+        vec![logo()]
     }
 
     #[zbus(property)]
     async fn tool_tip(&self) -> ToolTip {
+        let (title, description) = self.status.tool_tip(Utc::now());
+
         ToolTip {
             icon_name: "".into(),
             icon_pixmap: vec![],
-            title: matiane_core::NAME.into(),
-            description: "Activity logger.".into(),
+            title,
+            description,
+        }
+    }
+
+    async fn activate(&self, _x: i32, _y: i32) {
+        if self.commands.send(TrayCommand::Open).is_err() {
+            warn!("Daemon is not listening to the tray anymore.");
         }
     }
+
+    #[zbus(signal)]
+    async fn new_status(
+        emitter: &SignalEmitter<'_>,
+        status: &str,
+    ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn new_attention_icon(
+        emitter: &SignalEmitter<'_>,
+    ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn new_overlay_icon(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn new_tool_tip(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+/// Shows the new daemon state on the icon and in the menu.
+async fn update_status(
+    connection: &Connection,
+    status: TrayStatus,
+) -> Result<(), TrayError> {
+    let server = connection.object_server();
+    let tray = server.interface::<_, Tray>(TRAY_PATH).await?;
+    let menu = server.interface::<_, Menu>(MENU_PATH).await?;
+
+    let paused = status.paused;
+    let was_paused =
+        std::mem::replace(&mut tray.get_mut().await.status, status).paused;
+    let emitter = tray.signal_emitter();
+
+    tray.get().await.tool_tip_changed(emitter).await?;
+    Tray::new_tool_tip(emitter).await?;
+
+    if paused == was_paused {
+        return Ok(());
+    }
+
+    let state = tray.get().await.status().await;
+    tray.get().await.status_changed(emitter).await?;
+    Tray::new_status(emitter, &state).await?;
+    Tray::new_attention_icon(emitter).await?;
+    Tray::new_overlay_icon(emitter).await?;
+
+    let revision = menu.get_mut().await.set_paused(paused);
+    if let Some(revision) = revision {
+        Menu::layout_updated(menu.signal_emitter(), revision, 0).await?;
+    }
+
+    Ok(())
 }
 
 #[derive(Debug, Error)]
@@ -172,11 +282,19 @@ impl TrayConn<'_> {
 }
 
 pub fn spawn_tray(
+    mut status: watch::Receiver<TrayStatus>,
+    commands: UnboundedSender<TrayCommand>,
     token: CancellationToken,
 ) -> JoinHandle<Result<(), anyhow::Error>> {
     spawn(async move {
+        let tray = Tray {
+            status: status.borrow_and_update().clone(),
+            commands: commands.clone(),
+        };
+
         let connection = connection::Builder::session()?
-            .serve_at("/StatusNotifierItem", Tray)?
+            .serve_at(TRAY_PATH, tray)?
+            .serve_at(MENU_PATH, Menu::new(commands))?
             .build()
             .await?;
 
@@ -214,6 +332,17 @@ pub fn spawn_tray(
                     debug!("Shutting down.");
                     break;
                 },
+                changed = status.changed() => {
+                    if changed.is_err() {
+                        debug!("Daemon has stopped, shutting down.");
+                        break;
+                    }
+
+                    let current = status.borrow_and_update().clone();
+                    if let Err(err) = update_status(&connection, current).await {
+                        warn!("Could not update the tray: {}", err);
+                    }
+                },
                 chsignal = change_signal.next() => {
                     if chsignal.is_none() {
                         log::error!("DBus name owner change stream ended unexpectedly.");
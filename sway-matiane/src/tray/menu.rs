@@ -0,0 +1,301 @@
+// https://github.com/AyatanaIndicators/libdbusmenu/blob/master/libdbusmenu-glib/dbus-menu.xml
+
+use super::TrayCommand;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{OwnedValue, Type, Value};
+use zbus::{fdo, interface};
+
+pub const MENU_PATH: &str = "/MenuBar";
+
+const ROOT_ID: i32 = 0;
+const HALF_HOUR: Duration = Duration::from_secs(30 * 60);
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuItem {
+    Pause,
+    PauseHalfHour,
+    PauseHour,
+    Resume,
+    Separator,
+    Open,
+    Quit,
+}
+
+impl MenuItem {
+    pub const ALL: [MenuItem; 7] = [
+        MenuItem::Pause,
+        MenuItem::PauseHalfHour,
+        MenuItem::PauseHour,
+        MenuItem::Resume,
+        MenuItem::Separator,
+        MenuItem::Open,
+        MenuItem::Quit,
+    ];
+
+    pub fn id(self) -> i32 {
+        self as i32 + 1
+    }
+
+    pub fn from_id(id: i32) -> Option<Self> {
+        MenuItem::ALL.into_iter().find(|item| item.id() == id)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            MenuItem::Pause => "Pause tracking",
+            MenuItem::PauseHalfHour => "Pause for 30 min",
+            MenuItem::PauseHour => "Pause for 1 h",
+            MenuItem::Resume => "Resume",
+            MenuItem::Separator => "",
+            MenuItem::Open => "Open Matiane",
+            MenuItem::Quit => "Quit",
+        }
+    }
+
+    fn command(self) -> Option<TrayCommand> {
+        match self {
+            MenuItem::Pause => Some(TrayCommand::Pause(None)),
+            MenuItem::PauseHalfHour => {
+                Some(TrayCommand::Pause(Some(HALF_HOUR)))
+            }
+            MenuItem::PauseHour => Some(TrayCommand::Pause(Some(HOUR))),
+            MenuItem::Resume => Some(TrayCommand::Resume),
+            MenuItem::Separator => None,
+            MenuItem::Open => Some(TrayCommand::Open),
+            MenuItem::Quit => Some(TrayCommand::Quit),
+        }
+    }
+
+    fn enabled(self, paused: bool) -> bool {
+        match self {
+            MenuItem::Pause | MenuItem::PauseHalfHour | MenuItem::PauseHour => {
+                !paused
+            }
+            MenuItem::Resume => paused,
+            _ => true,
+        }
+    }
+}
+
+/// Menu item with its properties and submenu, `(ia{sv}av)` on the wire.
+#[derive(Debug, Serialize, Deserialize, Type, Value, OwnedValue)]
+pub struct Layout {
+    pub id: i32,
+    pub properties: HashMap<String, OwnedValue>,
+    pub children: Vec<OwnedValue>,
+}
+
+fn owned<'a>(value: impl Into<Value<'a>>) -> OwnedValue {
+    // Only file descriptors fail to convert.
+    value.into().try_to_owned().unwrap()
+}
+
+/// `com.canonical.dbusmenu` of the tray icon. Clicks are sent to the
+/// daemon as commands.
+#[derive(Debug)]
+pub struct Menu {
+    paused: bool,
+    revision: u32,
+    commands: UnboundedSender<TrayCommand>,
+}
+
+impl Menu {
+    pub fn new(commands: UnboundedSender<TrayCommand>) -> Self {
+        Menu {
+            paused: false,
+            revision: 1,
+            commands,
+        }
+    }
+
+    /// New layout revision when the menu has changed.
+    pub fn set_paused(&mut self, paused: bool) -> Option<u32> {
+        if self.paused == paused {
+            return None;
+        }
+
+        self.paused = paused;
+        self.revision += 1;
+        Some(self.revision)
+    }
+
+    fn properties(&self, item: MenuItem) -> HashMap<String, OwnedValue> {
+        let mut properties = HashMap::new();
+
+        if item == MenuItem::Separator {
+            properties.insert("type".into(), owned("separator"));
+            return properties;
+        }
+
+        properties.insert("label".into(), owned(item.label()));
+        properties.insert("enabled".into(), owned(item.enabled(self.paused)));
+
+        properties
+    }
+
+    fn item_layout(&self, item: MenuItem) -> Layout {
+        Layout {
+            id: item.id(),
+            properties: self.properties(item),
+            children: vec![],
+        }
+    }
+
+    fn root_layout(&self, depth: i32) -> Layout {
+        let children = match depth {
+            0 => vec![],
+            _ => MenuItem::ALL
+                .into_iter()
+                .map(|item| owned(self.item_layout(item)))
+                .collect(),
+        };
+
+        Layout {
+            id: ROOT_ID,
+            properties: HashMap::from([(
+                "children-display".into(),
+                owned("submenu"),
+            )]),
+            children,
+        }
+    }
+
+    fn click(&self, id: i32) -> fdo::Result<()> {
+        let item = MenuItem::from_id(id).ok_or_else(|| {
+            fdo::Error::InvalidArgs(format!("Unknown menu item {}.", id))
+        })?;
+
+        let Some(command) = item.command() else {
+            return Ok(());
+        };
+
+        if !item.enabled(self.paused) {
+            debug!("Ignoring the disabled {:?}.", item);
+            return Ok(());
+        }
+
+        if self.commands.send(command).is_err() {
+            warn!("Daemon is not listening to the tray anymore.");
+        }
+
+        Ok(())
+    }
+}
+
+#[interface(name = "com.canonical.dbusmenu")]
+impl Menu {
+    async fn get_layout(
+        &self,
+        parent_id: i32,
+        recursion_depth: i32,
+        _property_names: Vec<String>,
+    ) -> fdo::Result<(u32, Layout)> {
+        let layout = match parent_id {
+            ROOT_ID => self.root_layout(recursion_depth),
+            id => MenuItem::from_id(id)
+                .map(|item| self.item_layout(item))
+                .ok_or_else(|| {
+                    fdo::Error::InvalidArgs(format!(
+                        "Unknown menu item {}.",
+                        id
+                    ))
+                })?,
+        };
+
+        Ok((self.revision, layout))
+    }
+
+    async fn get_group_properties(
+        &self,
+        ids: Vec<i32>,
+        _property_names: Vec<String>,
+    ) -> Vec<(i32, HashMap<String, OwnedValue>)> {
+        ids.into_iter()
+            .filter_map(MenuItem::from_id)
+            .map(|item| (item.id(), self.properties(item)))
+            .collect()
+    }
+
+    async fn get_property(
+        &self,
+        id: i32,
+        name: String,
+    ) -> fdo::Result<OwnedValue> {
+        MenuItem::from_id(id)
+            .and_then(|item| self.properties(item).remove(&name))
+            .ok_or_else(|| {
+                fdo::Error::InvalidArgs(format!("No {} on item {}.", name, id))
+            })
+    }
+
+    async fn event(
+        &self,
+        id: i32,
+        event_id: String,
+        _data: OwnedValue,
+        _timestamp: u32,
+    ) -> fdo::Result<()> {
+        match event_id.as_str() {
+            "clicked" => self.click(id),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the ids that were not found.
+    async fn event_group(
+        &self,
+        events: Vec<(i32, String, OwnedValue, u32)>,
+    ) -> Vec<i32> {
+        events
+            .into_iter()
+            .filter_map(|(id, event_id, _, _)| match event_id.as_str() {
+                "clicked" => self.click(id).err().map(|_| id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    async fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    async fn about_to_show_group(
+        &self,
+        _ids: Vec<i32>,
+    ) -> (Vec<i32>, Vec<i32>) {
+        (vec![], vec![])
+    }
+
+    #[zbus(signal)]
+    pub async fn layout_updated(
+        emitter: &SignalEmitter<'_>,
+        revision: u32,
+        parent: i32,
+    ) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    async fn version(&self) -> u32 {
+        3
+    }
+
+    #[zbus(property)]
+    async fn text_direction(&self) -> String {
+        "ltr".into()
+    }
+
+    #[zbus(property)]
+    async fn status(&self) -> String {
+        "normal".into()
+    }
+
+    #[zbus(property)]
+    async fn icon_theme_path(&self) -> Vec<String> {
+        vec![]
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::Result;
+use chrono::TimeDelta;
+use std::path::PathBuf;
+use sway_matiane::activity::Snapshot;
+use sway_matiane::control::{
+    ControlRequest, ControlSocket, Request, Response, Status, send,
+};
+use tempfile::{Builder, TempDir};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+struct Control {
+    _dir: TempDir,
+    path: PathBuf,
+    requests: mpsc::Receiver<ControlRequest>,
+    token: CancellationToken,
+}
+
+async fn setup_control(name: &str) -> Result<Control> {
+    let dir = Builder::new()
+        .prefix(&format!("sway-matiane-{}", name))
+        .rand_bytes(10)
+        .tempdir()?;
+
+    // Left over from a daemon that was killed.
+    let path = dir.path().join("run").join("control.sock");
+    std::fs::create_dir(dir.path().join("run"))?;
+    std::fs::write(&path, b"")?;
+
+    let (sender, requests) = mpsc::channel(1);
+    let token = CancellationToken::new();
+    let socket = ControlSocket::bind(&path).await?;
+    socket.spawn(sender, token.clone());
+
+    Ok(Control {
+        _dir: dir,
+        path,
+        requests,
+        token,
+    })
+}
+
+/// Answers the requests like the daemon would.
+fn answer(mut requests: mpsc::Receiver<ControlRequest>) {
+    tokio::spawn(async move {
+        while let Some(request) = requests.recv().await {
+            let response = match &request.request {
+                Request::Status => Response::Status(Status {
+                    paused: false,
+                    paused_until: None,
+                    connected: true,
+                    activity: Snapshot {
+                        today: TimeDelta::minutes(5),
+                        ..Default::default()
+                    },
+                }),
+                Request::Pause { minutes: Some(0) } => {
+                    Response::error("Too short.")
+                }
+                _ => Response::Ok,
+            };
+
+            request.reply(response);
+        }
+    });
+}
+
+#[tokio::test]
+async fn control_requests() -> Result<()> {
+    let control = setup_control("control-requests").await?;
+    let path = control.path.clone();
+    answer(control.requests);
+
+    let Response::Status(status) = send(&path, &Request::Status).await? else {
+        panic!("Expected the status.");
+    };
+    assert!(status.connected);
+    assert_eq!(status.activity.today, TimeDelta::minutes(5));
+
+    assert_eq!(
+        send(&path, &Request::Pause { minutes: Some(0) }).await?,
+        Response::error("Too short.")
+    );
+    let note = Request::Annotate {
+        text: "lunch".into(),
+    };
+    assert_eq!(send(&path, &note).await?, Response::Ok);
+
+    control.token.cancel();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn control_wire_format() -> Result<()> {
+    let control = setup_control("control-wire").await?;
+    let stream = UnixStream::connect(&control.path).await?;
+    answer(control.requests);
+
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+
+    write
+        .write_all(b"{\"command\":\"pause\",\"minutes\":30}\n")
+        .await?;
+    assert_eq!(lines.next_line().await?, Some(r#"{"result":"ok"}"#.into()));
+
+    write
+        .write_all(b"{\"command\":\"reload-privacy\"}\n")
+        .await?;
+    assert_eq!(lines.next_line().await?, Some(r#"{"result":"ok"}"#.into()));
+
+    write.write_all(b"{\"command\":\"dance\"}\n").await?;
+    let line = lines.next_line().await?.unwrap_or_default();
+    assert!(line.starts_with(r#"{"result":"error","message":"Bad request:"#));
+
+    write.write_all(b"{\"command\":\"status\"}\n").await?;
+    let line = lines.next_line().await?.unwrap_or_default();
+    assert!(line.starts_with(r#"{"result":"status","paused":false,"#));
+    assert!(line.ends_with(r#""today":300}"#));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn control_socket_removed() -> Result<()> {
+    let control = setup_control("control-removed").await?;
+
+    control.token.cancel();
+    drop(control.requests);
+
+    for _ in 0..50 {
+        if !control.path.exists() {
+            return Ok(());
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    panic!("Control socket was not removed.");
+}
@@ -0,0 +1,172 @@
+use anyhow::Result;
+use futures::StreamExt;
+use matiane_core::events::Event;
+use std::time::Duration;
+use sway_matiane::logind::{Signals, watch};
+use tokio::time::timeout;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath};
+use zbus::{Connection, fdo, interface};
+
+mod util;
+
+const MANAGER_PATH: &str = "/org/freedesktop/login1";
+const SESSION_PATH: &str = "/org/freedesktop/login1/session/_31";
+
+struct Manager;
+
+#[interface(name = "org.freedesktop.login1.Manager")]
+impl Manager {
+    async fn get_session(
+        &self,
+        session_id: &str,
+    ) -> fdo::Result<OwnedObjectPath> {
+        if session_id != "1" {
+            return Err(fdo::Error::Failed("No such session.".into()));
+        }
+
+        Ok(ObjectPath::from_static_str_unchecked(SESSION_PATH).into())
+    }
+
+    #[zbus(signal)]
+    async fn prepare_for_sleep(
+        emitter: &SignalEmitter<'_>,
+        start: bool,
+    ) -> zbus::Result<()>;
+}
+
+struct Session {
+    idle_hint: bool,
+}
+
+#[interface(name = "org.freedesktop.login1.Session")]
+impl Session {
+    #[zbus(signal)]
+    async fn lock(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn unlock(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    async fn idle_hint(&self) -> bool {
+        self.idle_hint
+    }
+}
+
+/// Stand-in logind with the session `1`, not idle yet.
+async fn setup_logind() -> Result<(Connection, Connection)> {
+    p2p_connections!(
+        MANAGER_PATH => Manager,
+        SESSION_PATH => Session { idle_hint: false },
+    )
+    .await
+}
+
+async fn set_idle_hint(server: &Connection, idle_hint: bool) -> Result<()> {
+    let session = server
+        .object_server()
+        .interface::<_, Session>(SESSION_PATH)
+        .await?;
+
+    session.get_mut().await.idle_hint = idle_hint;
+    session
+        .get()
+        .await
+        .idle_hint_changed(session.signal_emitter())
+        .await?;
+
+    Ok(())
+}
+
+async fn next_event<S>(events: &mut S) -> Result<Event>
+where
+    S: StreamExt<Item = Event> + Unpin,
+{
+    timeout(Duration::from_secs(5), events.next())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Logind events have ended."))
+}
+
+#[tokio::test]
+async fn logind_sleep_and_lock() -> Result<()> {
+    let (server, client) = setup_logind().await?;
+
+    let signals = Signals {
+        sleep: true,
+        idle_hint: false,
+    };
+    let mut events = watch(&client, "1", signals).await?;
+
+    let manager = SignalEmitter::new(&server, MANAGER_PATH)?;
+    let session = SignalEmitter::new(&server, SESSION_PATH)?;
+
+    Manager::prepare_for_sleep(&manager, true).await?;
+    assert_eq!(next_event(&mut events).await?, Event::Sleep);
+
+    Manager::prepare_for_sleep(&manager, false).await?;
+    assert_eq!(next_event(&mut events).await?, Event::Awake);
+
+    Session::lock(&session).await?;
+    assert_eq!(next_event(&mut events).await?, Event::Sleep);
+
+    Session::unlock(&session).await?;
+    assert_eq!(next_event(&mut events).await?, Event::Awake);
+
+    // Suspended while locked, awake only once unlocked.
+    Session::lock(&session).await?;
+    assert_eq!(next_event(&mut events).await?, Event::Sleep);
+
+    Manager::prepare_for_sleep(&manager, true).await?;
+    Manager::prepare_for_sleep(&manager, false).await?;
+    assert!(
+        timeout(Duration::from_millis(100), events.next())
+            .await
+            .is_err()
+    );
+
+    Session::unlock(&session).await?;
+    assert_eq!(next_event(&mut events).await?, Event::Awake);
+
+    // Not asked for.
+    set_idle_hint(&server, true).await?;
+    assert!(
+        timeout(Duration::from_millis(100), events.next())
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn logind_idle_hint() -> Result<()> {
+    let (server, client) = setup_logind().await?;
+
+    let signals = Signals {
+        sleep: false,
+        idle_hint: true,
+    };
+    let mut events = watch(&client, "1", signals).await?;
+
+    set_idle_hint(&server, true).await?;
+    assert_eq!(next_event(&mut events).await?, Event::Idle);
+
+    set_idle_hint(&server, false).await?;
+    assert_eq!(next_event(&mut events).await?, Event::Active);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn logind_unknown_session() -> Result<()> {
+    let (_server, client) = setup_logind().await?;
+
+    let signals = Signals {
+        sleep: true,
+        idle_hint: true,
+    };
+
+    assert!(watch(&client, "2", signals).await.is_err());
+
+    Ok(())
+}
@@ -1,10 +1,17 @@
 use anyhow::Result;
 use futures::StreamExt;
+use matiane_core::events::{self, Focused};
 use std::path::PathBuf;
+use sway_matiane::focus::{current_focus, idle_event};
+use sway_matiane::sway::client::{ClientError, SwayClient};
 use sway_matiane::sway::codec::SwayPacketCodecError;
 use sway_matiane::sway::command::EventType;
+use sway_matiane::sway::command::{CommandType, CommandTypeError};
 use sway_matiane::sway::connection::{SubscribeError, subscribe};
-use sway_matiane::sway::reply::{CommandError, Event, WindowChange};
+use sway_matiane::sway::reply::{
+    CommandError, Event, ShutdownChange, WindowChange, WorkspaceChange,
+};
+use sway_matiane::sway::tree::{Location, locate};
 use tempfile::{Builder, TempDir};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{UnixListener, UnixStream};
@@ -24,7 +31,7 @@ async fn sway_window_events_1() -> Result<()> {
         handle,
     } = setup_mock_server("window-events-1", server_recv, server_send)?;
 
-    let mut subbed = subscribe(&bind_path, EventType::Window).await?;
+    let mut subbed = subscribe(&bind_path, &[EventType::Window]).await?;
     let single_event = subbed.next().await.unwrap()?;
 
     let Event::Window(window) = single_event else {
@@ -46,7 +53,94 @@ async fn sway_window_events_1() -> Result<()> {
     assert_eq!(window.container.rect.height, 1775);
 
     let second = subbed.next().await;
-    matches!(second, None);
+    assert!(second.is_none());
+
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sway_subscribe_several_events() -> Result<()> {
+    let request: &[u8] = br#"["workspace","mode","shutdown","tick"]"#;
+    let server_recv = raw_packet_with_body! {
+        header: [magic, (u32_ne request.len()), (u32_ne 2)],
+        body: request
+    };
+
+    let workspace: &[u8] = br#"{"change":"reload","current":null,"old":null}"#;
+    let mode: &[u8] = br#"{"change":"resize","pango_markup":false}"#;
+    let tick: &[u8] = br#"{"first":true,"payload":""}"#;
+    let shutdown: &[u8] = br#"{"change":"exit"}"#;
+
+    let server_send = [
+        raw_subscribe_success!(),
+        raw_packet_with_body! {
+            header: [
+                magic,
+                (u32_ne workspace.len()),
+                [be2ne_4 0x80, 0x00, 0x00, 0x00]
+            ],
+            body: workspace
+        },
+        raw_packet_with_body! {
+            header: [magic, (u32_ne mode.len()), [be2ne_4 0x80, 0x00, 0x00, 0x02]],
+            body: mode
+        },
+        raw_packet_with_body! {
+            header: [magic, (u32_ne tick.len()), [be2ne_4 0x80, 0x00, 0x00, 0x07]],
+            body: tick
+        },
+        raw_packet_with_body! {
+            header: [
+                magic,
+                (u32_ne shutdown.len()),
+                [be2ne_4 0x80, 0x00, 0x00, 0x06]
+            ],
+            body: shutdown
+        },
+    ]
+    .concat();
+
+    let MockServer {
+        dir: _dir,
+        bind_path,
+        handle,
+    } = setup_mock_server("several-events", server_recv, server_send)?;
+
+    let mut subbed = subscribe(
+        &bind_path,
+        &[
+            EventType::Workspace,
+            EventType::Mode,
+            EventType::Shutdown,
+            EventType::Tick,
+        ],
+    )
+    .await?;
+
+    let Event::Workspace(workspace) = subbed.next().await.unwrap()? else {
+        panic!("Must be a workspace event.");
+    };
+    assert_eq!(workspace.change, WorkspaceChange::Reload);
+    assert!(workspace.current.is_none());
+
+    let Event::Mode(mode) = subbed.next().await.unwrap()? else {
+        panic!("Must be a mode event.");
+    };
+    assert_eq!(mode.change, "resize");
+
+    let Event::Tick(tick) = subbed.next().await.unwrap()? else {
+        panic!("Must be a tick event.");
+    };
+    assert!(tick.first);
+
+    let Event::Shutdown(shutdown) = subbed.next().await.unwrap()? else {
+        panic!("Must be a shutdown event.");
+    };
+    assert_eq!(shutdown.change, ShutdownChange::Exit);
+
+    assert!(subbed.next().await.is_none());
 
     handle.await??;
 
@@ -119,15 +213,320 @@ generate_sway_bad_event_tests![
         SubscribeError::NotAnEvent(0),
     ],
     [
-        sway_bad_event_unsupported_event,
+        sway_bad_event_unknown_event,
+        raw_packet_with_body! {
+            header: [magic, (u32_ne 2), [be2ne_4 0x80, 0x00, 0x00, 0x09]],
+            body: br#"{}"#
+        },
+        SubscribeError::BadCommand(CommandTypeError::IncorrectEventType(9)),
+    ],
+    [
+        sway_bad_event_bad_payload,
         raw_packet_with_body! {
             header: [magic, (u32_ne 2), [be2ne_4 0x80, 0x00, 0x00, 0x00]],
             body: br#"{}"#
         },
-        SubscribeError::UnsupportedEvent(0),
+        SubscribeError::BadPayload(_),
     ],
 ];
 
+const TREE: &[u8] = br#"{
+    "id": 1, "name": "root", "type": "root", "border": "none",
+    "current_border_width": 0, "layout": "splith",
+    "rect": {"x": 0, "y": 0, "width": 1920, "height": 1080},
+    "window_rect": {"x": 0, "y": 0, "width": 0, "height": 0},
+    "deco_rect": {"x": 0, "y": 0, "width": 0, "height": 0},
+    "geometry": {"x": 0, "y": 0, "width": 0, "height": 0},
+    "urgent": false, "focused": false, "focus": [2], "sticky": false,
+    "floating_nodes": [],
+    "nodes": [{
+        "id": 2, "name": "eDP-1", "type": "output", "border": "none",
+        "current_border_width": 0, "layout": "output",
+        "rect": {"x": 0, "y": 0, "width": 1920, "height": 1080},
+        "window_rect": {"x": 0, "y": 0, "width": 0, "height": 0},
+        "deco_rect": {"x": 0, "y": 0, "width": 0, "height": 0},
+        "geometry": {"x": 0, "y": 0, "width": 0, "height": 0},
+        "urgent": false, "focused": false, "focus": [3], "sticky": false,
+        "floating_nodes": [],
+        "nodes": [{
+            "id": 3, "name": "2: mail", "type": "workspace", "num": 2,
+            "border": "none", "current_border_width": 0, "layout": "splith",
+            "rect": {"x": 0, "y": 0, "width": 1920, "height": 1080},
+            "window_rect": {"x": 0, "y": 0, "width": 0, "height": 0},
+            "deco_rect": {"x": 0, "y": 0, "width": 0, "height": 0},
+            "geometry": {"x": 0, "y": 0, "width": 0, "height": 0},
+            "urgent": false, "focused": false, "focus": [10],
+            "sticky": false, "floating_nodes": [],
+            "nodes": [{
+                "id": 10, "name": "mutt", "type": "con", "border": "pixel",
+                "current_border_width": 2, "layout": "none",
+                "rect": {"x": 0, "y": 0, "width": 1920, "height": 1080},
+                "window_rect": {"x": 0, "y": 0, "width": 1920, "height": 1080},
+                "deco_rect": {"x": 0, "y": 0, "width": 0, "height": 0},
+                "geometry": {"x": 0, "y": 0, "width": 800, "height": 600},
+                "urgent": false, "focused": true, "focus": [],
+                "sticky": false, "floating_nodes": [], "nodes": [],
+                "app_id": "Alacritty", "pid": 4242
+            }]
+        }]
+    }]
+}"#;
+
+#[tokio::test]
+async fn sway_client_get_tree() -> Result<()> {
+    let MockServer {
+        dir: _dir,
+        bind_path,
+        handle,
+    } = setup_mock_conversation(
+        "client-get-tree",
+        vec![(
+            raw_packet![magic, (u32_ne 0), (u32_ne 4)],
+            raw_packet_with_body! {
+                header: [magic, (u32_ne TREE.len()), (u32_ne 4)],
+                body: TREE
+            },
+        )],
+    )?;
+
+    let mut client = SwayClient::connect(&bind_path).await?;
+    let tree = client.get_tree().await?;
+
+    assert_eq!(
+        locate(&tree, 10),
+        Some(Location {
+            workspace: Some("2: mail".into()),
+            workspace_num: Some(2),
+            output: Some("eDP-1".into()),
+        })
+    );
+
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sway_current_focus() -> Result<()> {
+    let MockServer {
+        dir: _dir,
+        bind_path,
+        handle,
+    } = setup_mock_conversation(
+        "current-focus",
+        vec![(
+            raw_packet![magic, (u32_ne 0), (u32_ne 4)],
+            raw_packet_with_body! {
+                header: [magic, (u32_ne TREE.len()), (u32_ne 4)],
+                body: TREE
+            },
+        )],
+    )?;
+
+    let mut client = SwayClient::connect(&bind_path).await?;
+    // The executable is of whatever runs as the pid here.
+    let focused = current_focus(&mut client).await?.map(|focused| Focused {
+        executable: None,
+        ..focused
+    });
+
+    assert_eq!(
+        focused,
+        Some(Focused {
+            title: "mutt".into(),
+            id: "Alacritty".into(),
+            pid: 4242,
+            workspace: Some("2: mail".into()),
+            workspace_num: Some(2),
+            output: Some("eDP-1".into()),
+            executable: None,
+        })
+    );
+
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sway_idle_event() -> Result<()> {
+    let inhibited = String::from_utf8(TREE.to_vec())?
+        .replace(r#""pid": 4242"#, r#""pid": 4242, "inhibit_idle": true"#);
+    let inhibited = inhibited.as_bytes();
+
+    let MockServer {
+        dir: _dir,
+        bind_path,
+        handle,
+    } = setup_mock_conversation(
+        "idle-event",
+        vec![
+            (
+                raw_packet![magic, (u32_ne 0), (u32_ne 4)],
+                raw_packet_with_body! {
+                    header: [magic, (u32_ne TREE.len()), (u32_ne 4)],
+                    body: TREE
+                },
+            ),
+            (
+                raw_packet![magic, (u32_ne 0), (u32_ne 4)],
+                raw_packet_with_body! {
+                    header: [magic, (u32_ne inhibited.len()), (u32_ne 4)],
+                    body: inhibited
+                },
+            ),
+        ],
+    )?;
+
+    let mut client = SwayClient::connect(&bind_path).await?;
+
+    assert_eq!(idle_event(&mut client).await, events::Event::Idle);
+    assert_eq!(idle_event(&mut client).await, events::Event::Passive);
+
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sway_client_several_requests() -> Result<()> {
+    let version: &[u8] = br#"{"major":1,"minor":10,"patch":1,
+        "human_readable":"1.10.1","loaded_config_file_name":"/etc/sway/config"}"#;
+    let workspaces: &[u8] = br#"[{"id":3,"num":2,"name":"2: mail",
+        "visible":true,"focused":true,"urgent":false,"representation":null,
+        "rect":{"x":0,"y":0,"width":1920,"height":1080},"output":"eDP-1"}]"#;
+    let commands: &[u8] = br#"[{"success":true},
+        {"success":false,"parse_error":true,"error":"Unknown command"}]"#;
+    let tick: &[u8] = br#"{"success":true}"#;
+
+    let MockServer {
+        dir: _dir,
+        bind_path,
+        handle,
+    } = setup_mock_conversation(
+        "client-several-requests",
+        vec![
+            (
+                raw_packet![magic, (u32_ne 0), (u32_ne 7)],
+                raw_packet_with_body! {
+                    header: [magic, (u32_ne version.len()), (u32_ne 7)],
+                    body: version
+                },
+            ),
+            (
+                raw_packet![magic, (u32_ne 0), (u32_ne 1)],
+                raw_packet_with_body! {
+                    header: [magic, (u32_ne workspaces.len()), (u32_ne 1)],
+                    body: workspaces
+                },
+            ),
+            (
+                raw_packet_with_body! {
+                    header: [magic, (u32_ne 19), (u32_ne 0)],
+                    body: b"reload; nonsense 12"
+                },
+                raw_packet_with_body! {
+                    header: [magic, (u32_ne commands.len()), (u32_ne 0)],
+                    body: commands
+                },
+            ),
+            (
+                raw_packet_with_body! {
+                    header: [magic, (u32_ne 5), (u32_ne 10)],
+                    body: b"hello"
+                },
+                raw_packet_with_body! {
+                    header: [magic, (u32_ne tick.len()), (u32_ne 10)],
+                    body: tick
+                },
+            ),
+        ],
+    )?;
+
+    let mut client = SwayClient::connect(&bind_path).await?;
+
+    let version = client.get_version().await?;
+    assert_eq!(version.human_readable, "1.10.1");
+    assert_eq!((version.major, version.minor, version.patch), (1, 10, 1));
+
+    let workspaces = client.get_workspaces().await?;
+    assert_eq!(workspaces.len(), 1);
+    assert_eq!(workspaces[0].name, "2: mail");
+    assert_eq!(workspaces[0].output, "eDP-1");
+    assert!(workspaces[0].focused);
+
+    let outcomes = client.run_command("reload; nonsense 12").await?;
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes[0].is_ok());
+    assert!(matches!(
+        &outcomes[1],
+        Err(CommandError {
+            parse_error: true,
+            ..
+        })
+    ));
+
+    assert!(client.send_tick("hello").await?);
+
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sway_client_bad_response_type() -> Result<()> {
+    let MockServer {
+        dir: _dir,
+        bind_path,
+        handle,
+    } = setup_mock_conversation(
+        "client-bad-response",
+        vec![(
+            raw_packet![magic, (u32_ne 0), (u32_ne 4)],
+            raw_packet_with_body! {
+                header: [magic, (u32_ne 2), (u32_ne 1)],
+                body: br#"[]"#
+            },
+        )],
+    )?;
+
+    let mut client = SwayClient::connect(&bind_path).await?;
+    let tree = client.get_tree().await;
+
+    assert!(matches!(
+        tree,
+        Err(ClientError::IncorrectResponseType {
+            expected: CommandType::GetTree,
+            got: 1
+        })
+    ));
+
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sway_client_closed() -> Result<()> {
+    let MockServer {
+        dir: _dir,
+        bind_path,
+        handle,
+    } = setup_mock_conversation(
+        "client-closed",
+        vec![(raw_packet![magic, (u32_ne 0), (u32_ne 3)], vec![])],
+    )?;
+
+    let mut client = SwayClient::connect(&bind_path).await?;
+    let outputs = client.get_outputs().await;
+
+    assert!(matches!(outputs, Err(ClientError::Closed)));
+
+    handle.await??;
+
+    Ok(())
+}
+
 struct MockServer {
     dir: TempDir,
     bind_path: PathBuf,
@@ -155,7 +554,43 @@ fn setup_mock_server(
         assert_eq!(read_res, expect_recv.len());
         assert_eq!(dup, expect_recv);
 
-        stream.write(&send).await?;
+        stream.write_all(&send).await?;
+        stream.shutdown().await?;
+
+        Ok::<_, anyhow::Error>(stream)
+    });
+
+    Ok(MockServer {
+        dir,
+        bind_path,
+        handle,
+    })
+}
+
+/// Answers each expected request with its response, in order.
+fn setup_mock_conversation(
+    name: &str,
+    conversation: Vec<(Vec<u8>, Vec<u8>)>,
+) -> Result<MockServer> {
+    let dir = Builder::new()
+        .prefix(&format!("sway-matiane-{}", name))
+        .rand_bytes(10)
+        .tempdir()?;
+
+    let bind_path = dir.path().join("conversation.sock");
+    let bind = UnixListener::bind(&bind_path)?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _addr) = bind.accept().await?;
+
+        for (expect_recv, send) in conversation {
+            let mut dup: Vec<u8> = vec![0; expect_recv.len()];
+            stream.read_exact(&mut dup).await?;
+            assert_eq!(dup, expect_recv);
+
+            stream.write_all(&send).await?;
+        }
+
         stream.shutdown().await?;
 
         Ok::<_, anyhow::Error>(stream)
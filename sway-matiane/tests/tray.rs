@@ -0,0 +1,157 @@
+use anyhow::Result;
+use chrono::{TimeDelta, TimeZone, Utc};
+use matiane_core::events::Focused;
+use std::collections::HashMap;
+use std::time::Duration;
+use sway_matiane::activity::Snapshot;
+use sway_matiane::tray::menu::{Layout, MENU_PATH, Menu, MenuItem};
+use sway_matiane::tray::{TrayCommand, TrayStatus};
+use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+use zbus::{Connection, proxy};
+
+mod util;
+
+#[proxy(
+    interface = "com.canonical.dbusmenu",
+    default_service = "org.kde.StatusNotifierItem-1-1",
+    default_path = "/MenuBar",
+    gen_blocking = false
+)]
+trait DBusMenu {
+    fn get_layout(
+        &self,
+        parent_id: i32,
+        recursion_depth: i32,
+        property_names: &[&str],
+    ) -> zbus::Result<(u32, Layout)>;
+
+    fn event(
+        &self,
+        id: i32,
+        event_id: &str,
+        data: &zbus::zvariant::Value<'_>,
+        timestamp: u32,
+    ) -> zbus::Result<()>;
+}
+
+/// Tray menu along with the commands it sends.
+async fn setup_menu()
+-> Result<(Connection, Connection, UnboundedReceiver<TrayCommand>)> {
+    let (commands, receiver) = unbounded_channel();
+    let (server, client) =
+        p2p_connections!(MENU_PATH => Menu::new(commands)).await?;
+
+    Ok((server, client, receiver))
+}
+
+fn enabled_items(layout: Layout) -> Result<HashMap<i32, bool>> {
+    layout
+        .children
+        .into_iter()
+        .map(|child| {
+            let child = Layout::try_from(child)?;
+            let enabled = child
+                .properties
+                .get("enabled")
+                .map(bool::try_from)
+                .transpose()?
+                .unwrap_or(true);
+
+            Ok((child.id, enabled))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn tray_menu_layout() -> Result<()> {
+    let (server, client, _commands) = setup_menu().await?;
+    let proxy = DBusMenuProxy::new(&client).await?;
+
+    let (revision, layout) = proxy.get_layout(0, -1, &[]).await?;
+    let items = enabled_items(layout)?;
+
+    assert_eq!(items.len(), MenuItem::ALL.len());
+    assert!(items[&MenuItem::Pause.id()]);
+    assert!(!items[&MenuItem::Resume.id()]);
+
+    let menu = server
+        .object_server()
+        .interface::<_, Menu>(MENU_PATH)
+        .await?;
+    assert_eq!(menu.get_mut().await.set_paused(true), Some(revision + 1));
+    assert_eq!(menu.get_mut().await.set_paused(true), None);
+
+    let (updated, layout) = proxy.get_layout(0, -1, &[]).await?;
+    let items = enabled_items(layout)?;
+
+    assert_eq!(updated, revision + 1);
+    assert!(!items[&MenuItem::PauseHour.id()]);
+    assert!(items[&MenuItem::Resume.id()]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tray_menu_clicked() -> Result<()> {
+    let (_server, client, mut commands) = setup_menu().await?;
+    let proxy = DBusMenuProxy::new(&client).await?;
+    let data = zbus::zvariant::Value::from(0);
+
+    // Disabled while tracking.
+    proxy
+        .event(MenuItem::Resume.id(), "clicked", &data, 0)
+        .await?;
+    proxy
+        .event(MenuItem::Open.id(), "hovered", &data, 0)
+        .await?;
+    proxy
+        .event(MenuItem::PauseHalfHour.id(), "clicked", &data, 0)
+        .await?;
+
+    assert_eq!(
+        commands.recv().await,
+        Some(TrayCommand::Pause(Some(Duration::from_secs(30 * 60))))
+    );
+    assert!(commands.try_recv().is_err());
+
+    assert!(proxy.event(42, "clicked", &data, 0).await.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn tray_tool_tip() {
+    let now = Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap();
+    let mut status = TrayStatus::default();
+
+    assert_eq!(
+        status.tool_tip(now),
+        ("matiane".to_owned(), "Today: 0m 00s".to_owned())
+    );
+
+    status.activity = Snapshot {
+        focused: Some(Focused {
+            title: "Mail".into(),
+            id: "firefox".into(),
+            pid: 1,
+            ..Default::default()
+        }),
+        since: Some(now - TimeDelta::seconds(65)),
+        today: TimeDelta::minutes(90),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        status.tool_tip(now),
+        (
+            "firefox".to_owned(),
+            "Mail\nFocused for 1m 05s\nToday: 1h 30m 00s".to_owned()
+        )
+    );
+
+    status.paused = true;
+    assert_eq!(
+        status.tool_tip(now).1,
+        "Tracking is paused.\nMail\nToday: 1h 30m 00s"
+    );
+}
@@ -1,4 +1,4 @@
-/// A not at all necessary macro for fun, brought to you by tears.
+// A not at all necessary macro for fun, brought to you by tears.
 
 #[macro_export]
 macro_rules! raw_packet {
@@ -123,7 +123,7 @@ macro_rules! generate_sway_bad_subscribe_tests {
                     handle,
                 } = setup_mock_server(stringify!($name), server_recv, $subscribe_response)?;
 
-                let subbed = subscribe(&bind_path, EventType::Window).await;
+                let subbed = subscribe(&bind_path, &[EventType::Window]).await;
                 assert!(subbed.is_err());
                 let error = subbed.unwrap_err();
                 assert!(matches!(error, $error_pat));
@@ -156,7 +156,7 @@ macro_rules! generate_sway_bad_event_tests {
                     handle,
                 } = setup_mock_server(stringify!($name), server_recv, response)?;
 
-                let mut events = subscribe(&bind_path, EventType::Window).await?;
+                let mut events = subscribe(&bind_path, &[EventType::Window]).await?;
 
                 let event = events.next().await.expect("Must return something.");
                 assert!(event.is_err());
@@ -170,3 +170,26 @@ macro_rules! generate_sway_bad_event_tests {
         )*
     };
 }
+
+/// Serves the objects on one end of a private socket and connects a client
+/// to the other, resolves into `(server, client)`.
+#[macro_export]
+macro_rules! p2p_connections {
+    ($($path:expr => $object:expr),+$(,)?) => {
+        async {
+            let (server, client) = tokio::net::UnixStream::pair()?;
+
+            let server = zbus::connection::Builder::unix_stream(server)
+                .server(zbus::Guid::generate())?
+                .p2p()
+                $(.serve_at($path, $object)?)+
+                .build();
+            let client =
+                zbus::connection::Builder::unix_stream(client).p2p().build();
+
+            let (server, client) = tokio::try_join!(server, client)?;
+
+            anyhow::Ok((server, client))
+        }
+    };
+}